@@ -1,2 +1,260 @@
+use crate::host::ClientOptions;
+use cfg_if::cfg_if;
+use lemmy_api_common::lemmy_db_schema::{ListingType, SortType};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
 pub const LEMMY_UI_LEPTOS_LEMMY_HOST: &str = "localhost:8536";
 pub const LEMMY_UI_LEPTOS_LEMMY_HTTPS: bool = false;
+pub const LEMMY_UI_LEPTOS_LEMMY_API_VERSION: &str = "v3";
+
+/// file read at startup unless `LEMMY_UI_LEPTOS_CONFIG` names another one
+pub const LEMMY_UI_LEPTOS_CONFIG: &str = "lemmy-ui-leptos.toml";
+
+/// name of the meta tag used to hand the public part of the config to the browser
+pub const PUBLIC_CONFIG_META: &str = "lemmy-public-config";
+
+/// Settings of the running server, read once at startup from a TOML file and then overridden by
+/// `LEMMY_UI_LEPTOS_*` environment variables. Anything left out keeps its default.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+  pub lemmy: LemmyConfig,
+  pub flash: FlashConfig,
+  pub proxy: ProxyConfig,
+  /// the part that is also sent to the browser
+  #[serde(flatten)]
+  pub public: PublicConfig,
+}
+
+/// How the server reaches the Lemmy backend. Kept from the browser, as the host may well be internal.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LemmyConfig {
+  pub host: String,
+  pub https: bool,
+  pub api_version: String,
+}
+
+impl Default for LemmyConfig {
+  fn default() -> Self {
+    Self {
+      host: LEMMY_UI_LEPTOS_LEMMY_HOST.into(),
+      https: LEMMY_UI_LEPTOS_LEMMY_HTTPS,
+      api_version: LEMMY_UI_LEPTOS_LEMMY_API_VERSION.into(),
+    }
+  }
+}
+
+/// Signs the one-shot messages that forms posted without javascript carry over their redirect.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlashConfig {
+  /// at least 32 bytes. Left unset, a random one is made at startup, which is fine for a single
+  /// server but not for several behind one load balancer
+  pub secret: Option<String>,
+}
+
+impl core::fmt::Debug for FlashConfig {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("FlashConfig")
+      .field("secret", &self.secret.as_ref().map(|_| "[redacted]"))
+      .finish()
+  }
+}
+
+/// How `/api` and `/pictrs` requests of the browser are passed on to Lemmy.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxyConfig {
+  /// reverse proxies in front of this server, whose `Forwarded` and `X-Forwarded-For` headers name
+  /// the browser. Anyone else could name any address there, so theirs are ignored
+  pub trusted_proxies: Vec<IpAddr>,
+  /// how long a request to lemmy may take, its streamed body included, so long enough for an image
+  /// upload over a slow link
+  pub timeout_secs: u64,
+}
+
+impl Default for ProxyConfig {
+  fn default() -> Self {
+    Self {
+      trusted_proxies: vec![],
+      timeout_secs: 300,
+    }
+  }
+}
+
+impl ProxyConfig {
+  pub fn timeout(&self) -> core::time::Duration {
+    core::time::Duration::from_secs(self.timeout_secs)
+  }
+}
+
+/// Settings that are safe to write into every page, as the hydrated client needs them too.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PublicConfig {
+  pub cookie: CookieConfig,
+  pub ui: UiConfig,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CookieConfig {
+  /// left unset, cookies only go back to the exact host that set them
+  pub domain: Option<String>,
+  /// left unset, cookies are secure whenever the page was served over https
+  pub secure: Option<bool>,
+  pub max_age_secs: u64,
+}
+
+impl Default for CookieConfig {
+  fn default() -> Self {
+    Self {
+      domain: None,
+      secure: None,
+      max_age_secs: 604800,
+    }
+  }
+}
+
+impl CookieConfig {
+  pub fn max_age(&self) -> core::time::Duration {
+    core::time::Duration::from_secs(self.max_age_secs)
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+  pub default_theme: String,
+  pub default_listing_type: ListingType,
+  pub default_sort_type: SortType,
+  /// posts per page on screens at least 1536px wide, otherwise lemmy's default applies
+  pub wide_page_limit: i64,
+  /// posts per page on screens at least 2560px wide
+  pub widest_page_limit: i64,
+}
+
+impl Default for UiConfig {
+  fn default() -> Self {
+    Self {
+      default_theme: "retro".into(),
+      default_listing_type: ListingType::Local,
+      default_sort_type: SortType::Active,
+      wide_page_limit: 20,
+      widest_page_limit: 30,
+    }
+  }
+}
+
+impl AppConfig {
+  pub fn client_options(&self) -> ClientOptions {
+    ClientOptions {
+      domain: self.lemmy.host.clone(),
+      secure: self.lemmy.https,
+      api_version: self.lemmy.api_version.clone(),
+    }
+  }
+}
+
+#[cfg(feature = "ssr")]
+impl AppConfig {
+  /// Reads the file named by `LEMMY_UI_LEPTOS_CONFIG`, or `lemmy-ui-leptos.toml` when present, and
+  /// applies the environment on top.
+  pub fn load() -> std::io::Result<Self> {
+    let path = std::env::var("LEMMY_UI_LEPTOS_CONFIG").ok();
+
+    let config = match std::fs::read_to_string(path.as_deref().unwrap_or(LEMMY_UI_LEPTOS_CONFIG)) {
+      Ok(s) => Self::from_toml(&s)?,
+      // only an explicitly named file has to exist
+      Err(e) if path.is_none() && e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+      Err(e) => return Err(e),
+    };
+
+    let mut config = config.with_env()?;
+
+    // made here rather than per worker, as every worker has to accept what another one signed
+    let secret = config.flash.secret.get_or_insert_with(|| {
+      actix_web::cookie::Key::generate()
+        .master()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+    });
+    if secret.len() < 32 {
+      return Err(std::io::Error::other(
+        "flash.secret must be at least 32 bytes",
+      ));
+    }
+
+    Ok(config)
+  }
+
+  pub fn from_toml(s: &str) -> std::io::Result<Self> {
+    toml::from_str(s).map_err(std::io::Error::other)
+  }
+
+  fn with_env(mut self) -> std::io::Result<Self> {
+    fn env<T: serde::de::DeserializeOwned>(name: &str) -> std::io::Result<Option<T>> {
+      match std::env::var(name) {
+        // bare strings are accepted too, so that LEMMY_UI_LEPTOS_DEFAULT_THEME=dark works
+        Ok(v) => serde_json::from_str(&v)
+          .or_else(|_| serde_json::from_value(serde_json::Value::String(v)))
+          .map(Some)
+          .map_err(|e| std::io::Error::other(format!("{name}: {e}"))),
+        Err(_) => Ok(None),
+      }
+    }
+
+    macro_rules! override_from_env {
+      ($($name:literal => $field:expr),* $(,)?) => {
+        $(if let Some(v) = env($name)? {
+          $field = v;
+        })*
+      };
+    }
+
+    override_from_env! {
+      "LEMMY_UI_LEPTOS_LEMMY_HOST" => self.lemmy.host,
+      "LEMMY_UI_LEPTOS_LEMMY_HTTPS" => self.lemmy.https,
+      "LEMMY_UI_LEPTOS_LEMMY_API_VERSION" => self.lemmy.api_version,
+      "LEMMY_UI_LEPTOS_FLASH_SECRET" => self.flash.secret,
+      "LEMMY_UI_LEPTOS_PROXY_TRUSTED_PROXIES" => self.proxy.trusted_proxies,
+      "LEMMY_UI_LEPTOS_PROXY_TIMEOUT_SECS" => self.proxy.timeout_secs,
+      "LEMMY_UI_LEPTOS_COOKIE_DOMAIN" => self.public.cookie.domain,
+      "LEMMY_UI_LEPTOS_COOKIE_SECURE" => self.public.cookie.secure,
+      "LEMMY_UI_LEPTOS_COOKIE_MAX_AGE_SECS" => self.public.cookie.max_age_secs,
+      "LEMMY_UI_LEPTOS_DEFAULT_THEME" => self.public.ui.default_theme,
+      "LEMMY_UI_LEPTOS_DEFAULT_LISTING_TYPE" => self.public.ui.default_listing_type,
+      "LEMMY_UI_LEPTOS_DEFAULT_SORT_TYPE" => self.public.ui.default_sort_type,
+      "LEMMY_UI_LEPTOS_WIDE_PAGE_LIMIT" => self.public.ui.wide_page_limit,
+      "LEMMY_UI_LEPTOS_WIDEST_PAGE_LIMIT" => self.public.ui.widest_page_limit,
+    }
+
+    Ok(self)
+  }
+}
+
+/// The public config of the running server: on the server that in the context provided by the
+/// actix binary, in the browser the one the server wrote into the page, falling back to defaults.
+pub fn use_public_config() -> PublicConfig {
+  cfg_if! {
+      if #[cfg(feature="ssr")] {
+        leptos::use_context::<AppConfig>()
+          .map(|config| config.public)
+          .unwrap_or_default()
+      } else {
+        read_page_public_config().unwrap_or_default()
+      }
+  }
+}
+
+#[cfg(not(feature = "ssr"))]
+fn read_page_public_config() -> Option<PublicConfig> {
+  let content = leptos::document()
+    .query_selector(&format!("meta[name=\"{PUBLIC_CONFIG_META}\"]"))
+    .ok()??
+    .get_attribute("content")?;
+  serde_json::from_str(&content).ok()
+}
@@ -1,6 +1,63 @@
-use crate::errors::*;
+use crate::{
+  config::{use_public_config, CookieConfig},
+  errors::*,
+};
 use core::time::Duration;
 
+/// name of the cookie holding the user's jwt. It is HttpOnly, so only the server ever sees it: server
+/// functions pass it on to lemmy, and so does the proxy for the browser's api calls
+pub const AUTH_COOKIE: &str = "jwt";
+
+/// Attributes every cookie is written and removed with. Both the server and the browser get them
+/// from `CookieAttributes::new`, so that one can remove or overwrite what the other set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CookieAttributes {
+  pub domain: Option<String>,
+  pub path: &'static str,
+  pub secure: bool,
+}
+
+impl CookieAttributes {
+  /// The configured domain when `host` lies within it, as browsers drop cookies for any other
+  /// domain, and otherwise a host-only cookie. Secure as configured, or else whenever the page was
+  /// served over https.
+  pub fn new(config: &CookieConfig, host: &str, https: bool) -> Self {
+    let host = host_without_port(host).to_ascii_lowercase();
+
+    let domain = config
+      .domain
+      .as_deref()
+      .map(|d| d.trim_start_matches('.').to_ascii_lowercase())
+      .filter(|d| host == *d || host.ends_with(&format!(".{d}")));
+
+    Self {
+      domain,
+      path: "/",
+      secure: config.secure.unwrap_or(https),
+    }
+  }
+}
+
+fn host_without_port(host: &str) -> &str {
+  if host.starts_with('[') {
+    // ipv6, e.g. [::1]:1237
+    host.split_inclusive(']').next().unwrap_or(host)
+  } else {
+    host.split(':').next().unwrap_or(host)
+  }
+}
+
+#[cfg(not(feature = "ssr"))]
+fn cookie_attributes() -> CookieAttributes {
+  let location = leptos::window().location();
+
+  CookieAttributes::new(
+    &use_public_config().cookie,
+    &location.host().unwrap_or_default(),
+    location.protocol().ok().as_deref() == Some("https:"),
+  )
+}
+
 #[cfg(not(feature = "ssr"))]
 pub async fn get_cookie(path: &str) -> Result<Option<String>, LemmyAppError> {
   let r = wasm_cookies::get(path);
@@ -16,6 +73,7 @@ pub async fn get_cookie(path: &str) -> Result<Option<String>, LemmyAppError> {
 pub async fn set_cookie(path: &str, value: &str, expires: &Duration) -> Result<(), LemmyAppError> {
   use chrono::offset::Utc;
   use wasm_cookies::{cookies::*, set};
+  let attributes = cookie_attributes();
   let now = Utc::now();
   let d = now + *expires;
 
@@ -24,90 +82,105 @@ pub async fn set_cookie(path: &str, value: &str, expires: &Duration) -> Result<(
     value,
     &CookieOptions {
       same_site: SameSite::Strict,
-      secure: false,
+      secure: attributes.secure,
       expires: Some(std::borrow::Cow::Borrowed(&d.to_rfc2822())),
-      domain: Some("localhost"),
-      path: Some("/"),
+      domain: attributes.domain.as_deref(),
+      path: Some(attributes.path),
     },
   );
 
   Ok(())
 }
 
-#[cfg(not(feature = "ssr"))]
-pub async fn remove_cookie(path: &str) -> Result<(), LemmyAppError> {
-  // wasm_cookies::delete(path);
+#[cfg(feature = "ssr")]
+async fn cookie_attributes() -> Result<CookieAttributes, LemmyAppError> {
+  use actix_web::HttpRequest;
+  use leptos_actix::extract;
 
-  use chrono::offset::Utc;
-  use wasm_cookies::{cookies::*, set};
-  let now = Utc::now();
-  let d = now - core::time::Duration::from_secs(604800);
+  let req = extract::<HttpRequest>().await?;
 
-  set(
+  Ok(attributes_for(&req))
+}
+
+#[cfg(feature = "ssr")]
+pub(crate) fn attributes_for(req: &actix_web::HttpRequest) -> CookieAttributes {
+  // honours X-Forwarded-Host and X-Forwarded-Proto from a reverse proxy
+  let info = req.connection_info();
+
+  CookieAttributes::new(
+    &use_public_config().cookie,
+    info.host(),
+    info.scheme() == "https",
+  )
+}
+
+#[cfg(feature = "ssr")]
+pub async fn set_cookie(path: &str, value: &str, expires: &Duration) -> Result<(), LemmyAppError> {
+  insert_cookie(build_cookie(
     path,
-    "value",
-    &CookieOptions {
-      same_site: SameSite::Strict,
-      secure: false,
-      expires: Some(std::borrow::Cow::Borrowed(&d.to_rfc2822())),
-      domain: Some("localhost"),
-      path: Some("/"),
-    },
-  );
+    value,
+    expires,
+    &cookie_attributes().await?,
+  ));
 
   Ok(())
 }
 
 #[cfg(feature = "ssr")]
-pub async fn set_cookie(path: &str, value: &str, expires: &Duration) -> Result<(), LemmyAppError> {
-  use actix_web::{
-    cookie::{time::OffsetDateTime, Cookie, SameSite},
-    http::{header, header::HeaderValue},
-  };
-  use leptos::expect_context;
-  use leptos_actix::ResponseOptions;
+pub async fn set_auth_cookie(jwt: &str, expires: &Duration) -> Result<(), LemmyAppError> {
+  let mut cookie = build_cookie(AUTH_COOKIE, jwt, expires, &cookie_attributes().await?);
+  cookie.set_http_only(true);
 
-  let response = expect_context::<ResponseOptions>();
+  insert_cookie(cookie);
+
+  Ok(())
+}
 
-  let mut cookie = Cookie::build(path, value).finish();
+#[cfg(feature = "ssr")]
+pub(crate) fn build_cookie(
+  path: &str,
+  value: &str,
+  expires: &Duration,
+  attributes: &CookieAttributes,
+) -> actix_web::cookie::Cookie<'static> {
+  use actix_web::cookie::{time::OffsetDateTime, Cookie, SameSite};
+
+  let mut cookie = Cookie::build(path.to_string(), value.to_string()).finish();
   let now = OffsetDateTime::now_utc();
   let d = now + *expires;
 
   cookie.set_expires(d);
-  cookie.set_path("/");
-  cookie.set_domain("localhost");
-  cookie.set_secure(Some(false));
-  cookie.set_same_site(Some(SameSite::Strict));
-
-  if let Ok(cookie) = HeaderValue::from_str(&cookie.to_string()) {
-    response.insert_header(header::SET_COOKIE, cookie);
+  cookie.set_path(attributes.path);
+  if let Some(domain) = attributes.domain.clone() {
+    cookie.set_domain(domain);
   }
+  cookie.set_secure(Some(attributes.secure));
+  cookie.set_same_site(Some(SameSite::Strict));
 
-  Ok(())
+  cookie
 }
 
 #[cfg(feature = "ssr")]
-pub async fn remove_cookie(path: &str) -> Result<(), LemmyAppError> {
-  use actix_web::{
-    cookie::{time::OffsetDateTime, Cookie},
-    http::{header, header::HeaderValue},
-  };
+pub(crate) fn insert_cookie(cookie: actix_web::cookie::Cookie) {
+  use actix_web::http::{header, header::HeaderValue};
   use leptos::expect_context;
   use leptos_actix::ResponseOptions;
 
   let response = expect_context::<ResponseOptions>();
 
-  let mut cookie = Cookie::build(path, "").finish();
-  let now = OffsetDateTime::now_utc();
-  let d = now - Duration::from_secs(604800);
+  // appended, as a response may set more than one cookie. Encoded, as actix decodes what it reads
+  if let Ok(cookie) = HeaderValue::from_str(&cookie.encoded().to_string()) {
+    response.append_header(header::SET_COOKIE, cookie);
+  }
+}
 
-  cookie.set_expires(d);
-  cookie.set_domain("localhost");
-  cookie.set_path("/");
+#[cfg(feature = "ssr")]
+pub async fn remove_cookie(path: &str) -> Result<(), LemmyAppError> {
+  // same domain and path as when it was set, or the browser keeps it
+  let mut cookie = build_cookie(path, "", &Duration::ZERO, &cookie_attributes().await?);
+  cookie.make_removal();
 
-  if let Ok(cookie) = HeaderValue::from_str(&cookie.to_string()) {
-    response.insert_header(header::SET_COOKIE, cookie);
-  }
+  insert_cookie(cookie);
 
   Ok(())
 }
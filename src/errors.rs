@@ -14,6 +14,11 @@ pub enum LemmyAppErrorType {
   #[default]
   Unknown,
   NotFound,
+  Unauthorized,
+  Forbidden,
+  RateLimited,
+  Timeout,
+  ServiceUnavailable,
   InternalServerError,
   InternalClientError,
   ParamsError,
@@ -25,21 +30,73 @@ pub enum LemmyAppErrorType {
   MissingToken,
 
   MissingReason,
+  EmptyContent,
+
+  PasswordMismatch,
+  EmptyAnswer,
+  EmptyCaptchaAnswer,
+  EmptyEmail,
+}
+
+impl LemmyAppErrorType {
+  /// The HTTP status of a page that could not be rendered because of this error.
+  pub fn status_code(&self) -> u16 {
+    match self {
+      LemmyAppErrorType::NotFound
+      | LemmyAppErrorType::ParamsError
+      | LemmyAppErrorType::ApiError(
+        LemmyErrorType::CouldntFindPost
+        | LemmyErrorType::CouldntFindCommunity
+        | LemmyErrorType::CouldntFindPerson
+        | LemmyErrorType::CouldntFindObject
+        | LemmyErrorType::Deleted,
+      ) => 404,
+      LemmyAppErrorType::Unauthorized
+      | LemmyAppErrorType::Forbidden
+      | LemmyAppErrorType::MissingToken
+      | LemmyAppErrorType::ApiError(
+        LemmyErrorType::NotLoggedIn
+        | LemmyErrorType::NotAModerator
+        | LemmyErrorType::NotAnAdmin
+        | LemmyErrorType::NotAModOrAdmin
+        | LemmyErrorType::SiteBan
+        | LemmyErrorType::Banned
+        | LemmyErrorType::BannedFromCommunity
+        | LemmyErrorType::InstanceIsPrivate
+        | LemmyErrorType::ObjectIsNotPublic,
+      ) => 403,
+      LemmyAppErrorType::ServiceUnavailable
+      | LemmyAppErrorType::Timeout
+      | LemmyAppErrorType::RateLimited => 503,
+      _ => 500,
+    }
+  }
 }
 
 pub fn message_from_error(error: &LemmyAppError) -> String {
   let i18n = use_i18n();
 
-  let s = match error.error_type {
-    LemmyAppErrorType::ApiError(LemmyErrorType::IncorrectLogin) => {
-      t!(i18n, invalid_login)().to_string()
-    }
+  let s = match &error.error_type {
+    LemmyAppErrorType::ApiError(e) => api_error_message(e),
     LemmyAppErrorType::EmptyUsername => t!(i18n, empty_username)().to_string(),
     LemmyAppErrorType::EmptyPassword => t!(i18n, empty_password)().to_string(),
     LemmyAppErrorType::MissingReason => t!(i18n, empty_reason)().to_string(),
+    LemmyAppErrorType::EmptyContent => t!(i18n, empty_content)().to_string(),
+    LemmyAppErrorType::PasswordMismatch => t!(i18n, password_mismatch)().to_string(),
+    LemmyAppErrorType::EmptyAnswer => t!(i18n, empty_answer)().to_string(),
+    LemmyAppErrorType::EmptyCaptchaAnswer => t!(i18n, empty_captcha_answer)().to_string(),
+    LemmyAppErrorType::EmptyEmail => t!(i18n, empty_email)().to_string(),
+    LemmyAppErrorType::MissingToken => t!(i18n, missing_token)().to_string(),
     LemmyAppErrorType::InternalServerError => t!(i18n, internal)().to_string(),
+    LemmyAppErrorType::InternalClientError => t!(i18n, internal_client_error)().to_string(),
+    LemmyAppErrorType::ParamsError => t!(i18n, params_error)().to_string(),
+    LemmyAppErrorType::NotFound => t!(i18n, not_found)().to_string(),
+    LemmyAppErrorType::Unauthorized => t!(i18n, unauthorized)().to_string(),
+    LemmyAppErrorType::Forbidden => t!(i18n, forbidden)().to_string(),
+    LemmyAppErrorType::RateLimited => t!(i18n, rate_limited)().to_string(),
+    LemmyAppErrorType::Timeout => t!(i18n, timeout)().to_string(),
+    LemmyAppErrorType::ServiceUnavailable => t!(i18n, service_unavailable)().to_string(),
     LemmyAppErrorType::Unknown => t!(i18n, unknown)().to_string(),
-    _ => "An error without description".to_string(),
   };
 
   logging::error!("{} - {}", s, error.content);
@@ -47,6 +104,239 @@ pub fn message_from_error(error: &LemmyAppError) -> String {
   s
 }
 
+/// Locale key of the message for an error from lemmy, named after how lemmy serializes it, e.g.
+/// `api_error_couldnt_find_post` for `{"error":"couldnt_find_post"}`.
+pub fn api_error_key(error: &LemmyErrorType) -> Option<String> {
+  let value = serde_json::to_value(error).ok()?;
+  Some(format!("api_error_{}", value.get("error")?.as_str()?))
+}
+
+// one arm per error lemmy can return, so that a lemmy upgrade bringing another one does not
+// compile until it has a message
+fn api_error_message(error: &LemmyErrorType) -> String {
+  use LemmyErrorType::*;
+
+  let i18n = use_i18n();
+
+  match error {
+    AltTextLengthOverflow => t!(i18n, api_error_alt_text_length_overflow)().to_string(),
+    ApplicationQuestionRequired => t!(i18n, api_error_application_question_required)().to_string(),
+    BanExpirationInPast => t!(i18n, api_error_ban_expiration_in_past)().to_string(),
+    Banned => t!(i18n, api_error_banned)().to_string(),
+    BannedFromCommunity => t!(i18n, api_error_banned_from_community)().to_string(),
+    BioLengthOverflow => t!(i18n, api_error_bio_length_overflow)().to_string(),
+    BlockedUrl => t!(i18n, api_error_blocked_url)().to_string(),
+    CannotCreatePostOrCommentInDeletedOrRemovedCommunity => t!(
+      i18n,
+      api_error_cannot_create_post_or_comment_in_deleted_or_removed_community
+    )()
+    .to_string(),
+    CannotLeaveAdmin => t!(i18n, api_error_cannot_leave_admin)().to_string(),
+    CannotReceivePage => t!(i18n, api_error_cannot_receive_page)().to_string(),
+    CantBlockAdmin => t!(i18n, api_error_cant_block_admin)().to_string(),
+    CantBlockLocalInstance => t!(i18n, api_error_cant_block_local_instance)().to_string(),
+    CantBlockYourself => t!(i18n, api_error_cant_block_yourself)().to_string(),
+    CantEnablePrivateInstanceAndFederationTogether => t!(
+      i18n,
+      api_error_cant_enable_private_instance_and_federation_together
+    )()
+    .to_string(),
+    CaptchaIncorrect => t!(i18n, api_error_captcha_incorrect)().to_string(),
+    CommunityAlreadyExists => t!(i18n, api_error_community_already_exists)().to_string(),
+    CommunityBlockAlreadyExists => t!(i18n, api_error_community_block_already_exists)().to_string(),
+    CommunityFollowerAlreadyExists => {
+      t!(i18n, api_error_community_follower_already_exists)().to_string()
+    }
+    CommunityHasNoFollowers => t!(i18n, api_error_community_has_no_followers)().to_string(),
+    CommunityIsBlocked => t!(i18n, api_error_community_is_blocked)().to_string(),
+    CommunityModeratorAlreadyExists => {
+      t!(i18n, api_error_community_moderator_already_exists)().to_string()
+    }
+    CommunityUserAlreadyBanned => t!(i18n, api_error_community_user_already_banned)().to_string(),
+    ContradictingFilters => t!(i18n, api_error_contradicting_filters)().to_string(),
+    CouldntCreateAudioCaptcha => t!(i18n, api_error_couldnt_create_audio_captcha)().to_string(),
+    CouldntCreateComment => t!(i18n, api_error_couldnt_create_comment)().to_string(),
+    CouldntCreatePost => t!(i18n, api_error_couldnt_create_post)().to_string(),
+    CouldntCreatePrivateMessage => t!(i18n, api_error_couldnt_create_private_message)().to_string(),
+    CouldntCreateReport => t!(i18n, api_error_couldnt_create_report)().to_string(),
+    CouldntFindCommunity => t!(i18n, api_error_couldnt_find_community)().to_string(),
+    CouldntFindObject => t!(i18n, api_error_couldnt_find_object)().to_string(),
+    CouldntFindPerson => t!(i18n, api_error_couldnt_find_person)().to_string(),
+    CouldntFindPost => t!(i18n, api_error_couldnt_find_post)().to_string(),
+    CouldntFindUsernameOrEmail => t!(i18n, api_error_couldnt_find_username_or_email)().to_string(),
+    CouldntGenerateTotp => t!(i18n, api_error_couldnt_generate_totp)().to_string(),
+    CouldntGetComments => t!(i18n, api_error_couldnt_get_comments)().to_string(),
+    CouldntGetPosts => t!(i18n, api_error_couldnt_get_posts)().to_string(),
+    CouldntHidePost => t!(i18n, api_error_couldnt_hide_post)().to_string(),
+    CouldntLikeComment => t!(i18n, api_error_couldnt_like_comment)().to_string(),
+    CouldntLikePost => t!(i18n, api_error_couldnt_like_post)().to_string(),
+    CouldntMarkPostAsRead => t!(i18n, api_error_couldnt_mark_post_as_read)().to_string(),
+    CouldntParseTotpSecret => t!(i18n, api_error_couldnt_parse_totp_secret)().to_string(),
+    CouldntResolveReport => t!(i18n, api_error_couldnt_resolve_report)().to_string(),
+    CouldntSaveComment => t!(i18n, api_error_couldnt_save_comment)().to_string(),
+    CouldntSavePost => t!(i18n, api_error_couldnt_save_post)().to_string(),
+    CouldntSendWebmention => t!(i18n, api_error_couldnt_send_webmention)().to_string(),
+    CouldntSetAllEmailVerified => t!(i18n, api_error_couldnt_set_all_email_verified)().to_string(),
+    CouldntSetAllRegistrationsAccepted => {
+      t!(i18n, api_error_couldnt_set_all_registrations_accepted)().to_string()
+    }
+    CouldntUpdateComment => t!(i18n, api_error_couldnt_update_comment)().to_string(),
+    CouldntUpdateCommunity => t!(i18n, api_error_couldnt_update_community)().to_string(),
+    CouldntUpdateCommunityHiddenStatus => {
+      t!(i18n, api_error_couldnt_update_community_hidden_status)().to_string()
+    }
+    CouldntUpdatePersonMentions => t!(i18n, api_error_couldnt_update_person_mentions)().to_string(),
+    CouldntUpdatePost => t!(i18n, api_error_couldnt_update_post)().to_string(),
+    CouldntUpdatePrivate => t!(i18n, api_error_couldnt_update_private)().to_string(),
+    CouldntUpdatePrivateMessage => t!(i18n, api_error_couldnt_update_private_message)().to_string(),
+    CouldntUpdateReplies => t!(i18n, api_error_couldnt_update_replies)().to_string(),
+    CouldntUpdateUser => t!(i18n, api_error_couldnt_update_user)().to_string(),
+    Deleted => t!(i18n, api_error_deleted)().to_string(),
+    DomainBlocked(domain) => t_string!(i18n, api_error_domain_blocked, domain = domain).to_string(),
+    DomainNotInAllowList(domain) => {
+      t_string!(i18n, api_error_domain_not_in_allow_list, domain = domain).to_string()
+    }
+    DownvotesAreDisabled => t!(i18n, api_error_downvotes_are_disabled)().to_string(),
+    EditPrivateMessageNotAllowed => {
+      t!(i18n, api_error_edit_private_message_not_allowed)().to_string()
+    }
+    EmailAlreadyExists => t!(i18n, api_error_email_already_exists)().to_string(),
+    EmailNotVerified => t!(i18n, api_error_email_not_verified)().to_string(),
+    EmailRequired => t!(i18n, api_error_email_required)().to_string(),
+    EmailSendFailed => t!(i18n, api_error_email_send_failed)().to_string(),
+    EmailSmtpServerNeedsAPort => t!(i18n, api_error_email_smtp_server_needs_a_port)().to_string(),
+    FederationDisabled => t!(i18n, api_error_federation_disabled)().to_string(),
+    FederationDisabledByStrictAllowList => {
+      t!(i18n, api_error_federation_disabled_by_strict_allow_list)().to_string()
+    }
+    FederationForbiddenByStrictAllowList => {
+      t!(i18n, api_error_federation_forbidden_by_strict_allow_list)().to_string()
+    }
+    HoneypotFailed => t!(i18n, api_error_honeypot_failed)().to_string(),
+    ImageUrlMissingLastPathSegment => {
+      t!(i18n, api_error_image_url_missing_last_path_segment)().to_string()
+    }
+    ImageUrlMissingPathSegments => {
+      t!(i18n, api_error_image_url_missing_path_segments)().to_string()
+    }
+    InboxTimeout => t!(i18n, api_error_inbox_timeout)().to_string(),
+    IncorrectLogin => t!(i18n, api_error_incorrect_login)().to_string(),
+    IncorrectTotpToken => t!(i18n, api_error_incorrect_totp_token)().to_string(),
+    InstanceBlockAlreadyExists => t!(i18n, api_error_instance_block_already_exists)().to_string(),
+    InstanceIsBlocked => t!(i18n, api_error_instance_is_blocked)().to_string(),
+    InstanceIsPrivate => t!(i18n, api_error_instance_is_private)().to_string(),
+    InvalidBodyField => t!(i18n, api_error_invalid_body_field)().to_string(),
+    InvalidBotAction => t!(i18n, api_error_invalid_bot_action)().to_string(),
+    InvalidCommunity => t!(i18n, api_error_invalid_community)().to_string(),
+    InvalidDefaultPostListingType => {
+      t!(i18n, api_error_invalid_default_post_listing_type)().to_string()
+    }
+    InvalidDisplayName => t!(i18n, api_error_invalid_display_name)().to_string(),
+    InvalidMatrixId => t!(i18n, api_error_invalid_matrix_id)().to_string(),
+    InvalidName => t!(i18n, api_error_invalid_name)().to_string(),
+    InvalidPassword => t!(i18n, api_error_invalid_password)().to_string(),
+    InvalidPostTitle => t!(i18n, api_error_invalid_post_title)().to_string(),
+    InvalidQuery => t!(i18n, api_error_invalid_query)().to_string(),
+    InvalidRegex => t!(i18n, api_error_invalid_regex)().to_string(),
+    InvalidUnixTime => t!(i18n, api_error_invalid_unix_time)().to_string(),
+    InvalidUrl => t!(i18n, api_error_invalid_url)().to_string(),
+    InvalidUrlScheme => t!(i18n, api_error_invalid_url_scheme)().to_string(),
+    InvalidVoteValue => t!(i18n, api_error_invalid_vote_value)().to_string(),
+    LanguageNotAllowed => t!(i18n, api_error_language_not_allowed)().to_string(),
+    LocalSiteNotSetup => t!(i18n, api_error_local_site_not_setup)().to_string(),
+    Locked => t!(i18n, api_error_locked)().to_string(),
+    MaxCommentDepthReached => t!(i18n, api_error_max_comment_depth_reached)().to_string(),
+    MissingAnEmail => t!(i18n, api_error_missing_an_email)().to_string(),
+    MissingTotpSecret => t!(i18n, api_error_missing_totp_secret)().to_string(),
+    MissingTotpToken => t!(i18n, api_error_missing_totp_token)().to_string(),
+    NewPostCannotBeLocked => t!(i18n, api_error_new_post_cannot_be_locked)().to_string(),
+    NoAdmins => t!(i18n, api_error_no_admins)().to_string(),
+    NoCommentEditAllowed => t!(i18n, api_error_no_comment_edit_allowed)().to_string(),
+    NoCommunityFoundInCc => t!(i18n, api_error_no_community_found_in_cc)().to_string(),
+    NoContentTypeHeader => t!(i18n, api_error_no_content_type_header)().to_string(),
+    NoEmailSetup => t!(i18n, api_error_no_email_setup)().to_string(),
+    NoIdGiven => t!(i18n, api_error_no_id_given)().to_string(),
+    NoLinesInHtml => t!(i18n, api_error_no_lines_in_html)().to_string(),
+    NoPostEditAllowed => t!(i18n, api_error_no_post_edit_allowed)().to_string(),
+    NotAModOrAdmin => t!(i18n, api_error_not_a_mod_or_admin)().to_string(),
+    NotAModerator => t!(i18n, api_error_not_a_moderator)().to_string(),
+    NotAnAdmin => t!(i18n, api_error_not_an_admin)().to_string(),
+    NotAnImageType => t!(i18n, api_error_not_an_image_type)().to_string(),
+    NotHigherAdmin => t!(i18n, api_error_not_higher_admin)().to_string(),
+    NotHigherMod => t!(i18n, api_error_not_higher_mod)().to_string(),
+    NotLoggedIn => t!(i18n, api_error_not_logged_in)().to_string(),
+    NotTopAdmin => t!(i18n, api_error_not_top_admin)().to_string(),
+    NotTopMod => t!(i18n, api_error_not_top_mod)().to_string(),
+    ObjectIsNotPublic => t!(i18n, api_error_object_is_not_public)().to_string(),
+    ObjectNotLocal => t!(i18n, api_error_object_not_local)().to_string(),
+    OnlyAdminsCanCreateCommunities => {
+      t!(i18n, api_error_only_admins_can_create_communities)().to_string()
+    }
+    OnlyLocalAdminCanRemoveCommunity => {
+      t!(i18n, api_error_only_local_admin_can_remove_community)().to_string()
+    }
+    OnlyLocalAdminCanRestoreCommunity => {
+      t!(i18n, api_error_only_local_admin_can_restore_community)().to_string()
+    }
+    OnlyModsCanPostInCommunity => t!(i18n, api_error_only_mods_can_post_in_community)().to_string(),
+    PageDoesNotSpecifyCreator => t!(i18n, api_error_page_does_not_specify_creator)().to_string(),
+    PageDoesNotSpecifyGroup => t!(i18n, api_error_page_does_not_specify_group)().to_string(),
+    PasswordResetLimitReached => t!(i18n, api_error_password_reset_limit_reached)().to_string(),
+    PasswordsDoNotMatch => t!(i18n, api_error_passwords_do_not_match)().to_string(),
+    PermissiveRegex => t!(i18n, api_error_permissive_regex)().to_string(),
+    PersonBlockAlreadyExists => t!(i18n, api_error_person_block_already_exists)().to_string(),
+    PersonIsBannedFromCommunity => {
+      t!(i18n, api_error_person_is_banned_from_community)().to_string()
+    }
+    PersonIsBannedFromSite(user) => {
+      t_string!(i18n, api_error_person_is_banned_from_site, user = user).to_string()
+    }
+    PersonIsBlocked => t!(i18n, api_error_person_is_blocked)().to_string(),
+    PictrsApiKeyNotProvided => t!(i18n, api_error_pictrs_api_key_not_provided)().to_string(),
+    PictrsCachingDisabled => t!(i18n, api_error_pictrs_caching_disabled)().to_string(),
+    PictrsPurgeResponseError(error) => {
+      t_string!(i18n, api_error_pictrs_purge_response_error, error = error).to_string()
+    }
+    PictrsResponseError(error) => {
+      t_string!(i18n, api_error_pictrs_response_error, error = error).to_string()
+    }
+    PostIsLocked => t!(i18n, api_error_post_is_locked)().to_string(),
+    PostTitleTooLong => t!(i18n, api_error_post_title_too_long)().to_string(),
+    RateLimitError => t!(i18n, api_error_rate_limit_error)().to_string(),
+    RegistrationApplicationAnswerRequired => {
+      t!(i18n, api_error_registration_application_answer_required)().to_string()
+    }
+    RegistrationApplicationIsPending => {
+      t!(i18n, api_error_registration_application_is_pending)().to_string()
+    }
+    RegistrationClosed => t!(i18n, api_error_registration_closed)().to_string(),
+    RegistrationDenied(reason) => {
+      t_string!(i18n, api_error_registration_denied, reason = reason).to_string()
+    }
+    ReportReasonRequired => t!(i18n, api_error_report_reason_required)().to_string(),
+    ReportTooLong => t!(i18n, api_error_report_too_long)().to_string(),
+    SiteAlreadyExists => t!(i18n, api_error_site_already_exists)().to_string(),
+    SiteBan => t!(i18n, api_error_site_ban)().to_string(),
+    SiteDescriptionLengthOverflow => {
+      t!(i18n, api_error_site_description_length_overflow)().to_string()
+    }
+    SiteMetadataPageIsNotDoctypeHtml => {
+      t!(i18n, api_error_site_metadata_page_is_not_doctype_html)().to_string()
+    }
+    SiteNameLengthOverflow => t!(i18n, api_error_site_name_length_overflow)().to_string(),
+    SiteNameRequired => t!(i18n, api_error_site_name_required)().to_string(),
+    Slurs => t!(i18n, api_error_slurs)().to_string(),
+    SystemErrLogin => t!(i18n, api_error_system_err_login)().to_string(),
+    TokenNotFound => t!(i18n, api_error_token_not_found)().to_string(),
+    TooManyItems => t!(i18n, api_error_too_many_items)().to_string(),
+    TotpAlreadyEnabled => t!(i18n, api_error_totp_already_enabled)().to_string(),
+    Unknown(error) => t_string!(i18n, api_error_unknown, error = error).to_string(),
+    UrlLengthOverflow => t!(i18n, api_error_url_length_overflow)().to_string(),
+    UrlWithoutDomain => t!(i18n, api_error_url_without_domain)().to_string(),
+    UserAlreadyExists => t!(i18n, api_error_user_already_exists)().to_string(),
+  }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct LemmyAppError {
   pub error_type: LemmyAppErrorType,
@@ -64,20 +354,31 @@ impl core::fmt::Debug for LemmyAppError {
   }
 }
 
+// as json, which `from_str` reads back, so that it can travel as the custom error of a
+// `ServerFnError<LemmyAppError>`
 impl core::fmt::Display for LemmyAppError {
   fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-    match &self.error_type {
-      LemmyAppErrorType::ApiError(inner) => {
-        write!(
-          f,
-          "{{\"error_type\":{{\"{}\": {}}}}}",
-          &self.error_type,
-          serde_json::to_string(inner).ok().unwrap()
-        )
-      }
-      _ => {
-        write!(f, "{{\"error_type\":\"{}\"}}", &self.error_type)
-      }
+    f.write_str(&serde_json::to_string(self).map_err(|_| core::fmt::Error)?)
+  }
+}
+
+impl core::str::FromStr for LemmyAppError {
+  type Err = serde_json::Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    serde_json::from_str(s)
+  }
+}
+
+/// The error of a server function, as seen by the hydrated app that called it.
+impl From<ServerFnError<LemmyAppError>> for LemmyAppError {
+  fn from(value: ServerFnError<LemmyAppError>) -> Self {
+    match value {
+      ServerFnError::WrappedServerError(e) => e,
+      e => Self {
+        error_type: LemmyAppErrorType::InternalServerError,
+        content: e.to_string(),
+      },
     }
   }
 }
@@ -182,9 +483,19 @@ impl From<awc::error::JsonPayloadError> for LemmyAppError {
 #[cfg(feature = "ssr")]
 impl From<awc::error::SendRequestError> for LemmyAppError {
   fn from(value: awc::error::SendRequestError) -> Self {
+    use awc::error::{ConnectError, SendRequestError};
     use std::error::Error;
+
+    let error_type = match value {
+      SendRequestError::Timeout | SendRequestError::Connect(ConnectError::Timeout) => {
+        LemmyAppErrorType::Timeout
+      }
+      SendRequestError::Connect(_) => LemmyAppErrorType::ServiceUnavailable,
+      _ => LemmyAppErrorType::InternalServerError,
+    };
+
     Self {
-      error_type: LemmyAppErrorType::InternalServerError,
+      error_type,
       content: format!("{} - source: {:?}", value, value.source()),
     }
   }
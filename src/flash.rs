@@ -0,0 +1,222 @@
+use crate::errors::LemmyAppError;
+#[cfg(feature = "ssr")]
+use crate::{
+  config::FlashConfig,
+  cookie::{attributes_for, build_cookie, insert_cookie},
+  errors::LemmyAppResult,
+};
+#[cfg(feature = "ssr")]
+use core::time::Duration;
+use leptos::*;
+
+/// name of the cookie carrying a flash over a redirect
+pub const FLASH_COOKIE: &str = "flash";
+
+/// name of the meta tag used to hand the flash of the current page to the browser
+pub const FLASH_META: &str = "lemmy-flash";
+
+// only has to outlive the redirect it is sent with
+#[cfg(feature = "ssr")]
+const FLASH_MAX_AGE: Duration = Duration::from_secs(60);
+
+/// The error of a form posted without javascript, carried over the redirect that follows it in a
+/// short-lived signed cookie, so that it is shown once on the page the redirect leads to without
+/// ending up in its URL. Provided by `App`, and cleared by `TopNav` on the next navigation.
+#[derive(Clone, Copy)]
+pub struct Flash(RwSignal<Option<LemmyAppError>>);
+
+impl Flash {
+  pub fn error(&self) -> Option<LemmyAppError> {
+    self.0.get_untracked()
+  }
+
+  pub fn clear(&self) {
+    self.0.set(None);
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(&self.error()).unwrap_or_default()
+  }
+}
+
+/// Takes the flash sent with this request (on the server) or written into the page (in the browser).
+pub fn provide_flash() -> Flash {
+  let flash = Flash(create_rw_signal(take_flash()));
+  provide_context(flash);
+  flash
+}
+
+pub fn use_flash() -> Flash {
+  expect_context::<Flash>()
+}
+
+#[cfg(feature = "ssr")]
+fn take_flash() -> Option<LemmyAppError> {
+  use actix_web::{cookie::CookieJar, HttpRequest};
+
+  let req = use_context::<HttpRequest>()?;
+  let key = use_context::<FlashKey>()?;
+  let cookie = req.cookie(FLASH_COOKIE)?;
+
+  // shown once, so removed with this response whether or not it checks out
+  let mut removal = build_cookie(FLASH_COOKIE, "", &Duration::ZERO, &attributes_for(&req));
+  removal.make_removal();
+  insert_cookie(removal);
+
+  let mut jar = CookieJar::new();
+  jar.add_original(cookie);
+  let cookie = jar.signed(&key.0).get(FLASH_COOKIE)?;
+
+  serde_json::from_str(cookie.value()).ok()
+}
+
+#[cfg(not(feature = "ssr"))]
+fn take_flash() -> Option<LemmyAppError> {
+  let content = document()
+    .query_selector(&format!("meta[name=\"{FLASH_META}\"]"))
+    .ok()??
+    .get_attribute("content")?;
+  serde_json::from_str::<Option<LemmyAppError>>(&content)
+    .ok()
+    .flatten()
+}
+
+/// Signs and checks flash cookies. Made once by `server::configure` and provided to every render
+/// and server function.
+#[cfg(feature = "ssr")]
+#[derive(Clone)]
+pub struct FlashKey(actix_web::cookie::Key);
+
+#[cfg(feature = "ssr")]
+impl FlashKey {
+  pub fn new(config: &FlashConfig) -> Self {
+    use actix_web::cookie::Key;
+
+    match config.secret.as_deref() {
+      Some(secret) if secret.len() >= 32 => Self(Key::derive_from(secret.as_bytes())),
+      // `AppConfig::load` makes sure there is one, this is for configs built in code
+      _ => Self(Key::generate()),
+    }
+  }
+}
+
+/// Redirects a form posted without javascript to `path`, where `error` is shown once.
+#[cfg(feature = "ssr")]
+async fn redirect_with_error(path: &str, error: &LemmyAppError) -> Result<(), LemmyAppError> {
+  use actix_web::{cookie::CookieJar, HttpRequest};
+  use leptos_actix::{extract, redirect};
+
+  let req = extract::<HttpRequest>().await?;
+  let key = expect_context::<FlashKey>();
+
+  let mut cookie = build_cookie(
+    FLASH_COOKIE,
+    &serde_json::to_string(error)?,
+    &FLASH_MAX_AGE,
+    &attributes_for(&req),
+  );
+  cookie.set_http_only(true);
+
+  let mut jar = CookieJar::new();
+  jar.signed_mut(&key.0).add(cookie);
+  if let Some(cookie) = jar.get(FLASH_COOKIE) {
+    insert_cookie(cookie.clone());
+  }
+
+  redirect(path);
+
+  Ok(())
+}
+
+/// Keeps `value` in the browser under `name` for `max_age`, encrypted with the flash key so that
+/// only this server can read it back. For what a form posted without javascript needs again on the
+/// pages after its redirect.
+#[cfg(feature = "ssr")]
+pub async fn set_private_cookie(name: &str, value: &str, max_age: &Duration) -> LemmyAppResult<()> {
+  use actix_web::{cookie::CookieJar, HttpRequest};
+  use leptos_actix::extract;
+
+  let req = extract::<HttpRequest>().await?;
+  let key = expect_context::<FlashKey>();
+
+  let mut cookie = build_cookie(name, value, max_age, &attributes_for(&req));
+  cookie.set_http_only(true);
+
+  let mut jar = CookieJar::new();
+  jar.private_mut(&key.0).add(cookie);
+  if let Some(cookie) = jar.get(name) {
+    insert_cookie(cookie.clone());
+  }
+
+  Ok(())
+}
+
+/// What `set_private_cookie` kept under `name`, where it checks out.
+#[cfg(feature = "ssr")]
+pub async fn get_private_cookie(name: &str) -> Option<String> {
+  use actix_web::{cookie::CookieJar, HttpRequest};
+  use leptos_actix::extract;
+
+  let req = extract::<HttpRequest>().await.ok()?;
+  let key = use_context::<FlashKey>()?;
+  let cookie = req.cookie(name)?;
+
+  let mut jar = CookieJar::new();
+  jar.add_original(cookie);
+  let cookie = jar.private(&key.0).get(name)?;
+
+  Some(cookie.value().to_string())
+}
+
+/// Whether this server function call is a form posted by a browser without javascript, rather
+/// than a call from the hydrated app. Only the former asks for html.
+#[cfg(feature = "ssr")]
+pub async fn is_form_post() -> bool {
+  use actix_web::{http::header, HttpRequest};
+  use leptos_actix::extract;
+
+  extract::<HttpRequest>()
+    .await
+    .ok()
+    .and_then(|req| {
+      req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .map(|accept| accept.contains("text/html"))
+    })
+    .unwrap_or(false)
+}
+
+/// What a server function returns for `error`. A form posted without javascript is also sent to
+/// `path`, where the error is flashed, while the hydrated app handles the error itself.
+#[cfg(feature = "ssr")]
+pub async fn server_fn_error(path: &str, error: LemmyAppError) -> ServerFnError<LemmyAppError> {
+  if is_form_post().await {
+    if let Err(e) = redirect_with_error(path, &error).await {
+      logging::error!("could not flash {:?}: {:?}", error, e);
+    }
+  }
+
+  ServerFnError::WrappedServerError(error)
+}
+
+/// What a server function behind a form returns for `result`. A form posted without javascript is
+/// also sent on, to `ok_to` (else `back`) once done, or to `back` with the error flashed. The
+/// hydrated app handles either itself.
+#[cfg(feature = "ssr")]
+pub async fn finish_form<T>(
+  result: LemmyAppResult<T>,
+  back: &str,
+  ok_to: Option<&str>,
+) -> Result<T, ServerFnError<LemmyAppError>> {
+  match result {
+    Ok(o) => {
+      if is_form_post().await {
+        leptos_actix::redirect(ok_to.unwrap_or(back));
+      }
+      Ok(o)
+    }
+    Err(e) => Err(server_fn_error(back, e).await),
+  }
+}
@@ -1,62 +1,47 @@
-use crate::config::{LEMMY_UI_LEPTOS_LEMMY_HOST, LEMMY_UI_LEPTOS_LEMMY_HTTPS};
+#[cfg(not(feature = "ssr"))]
+use crate::config::{
+  LEMMY_UI_LEPTOS_LEMMY_API_VERSION,
+  LEMMY_UI_LEPTOS_LEMMY_HOST,
+  LEMMY_UI_LEPTOS_LEMMY_HTTPS,
+};
 use cfg_if::cfg_if;
-// use lemmy_client::{ClientOptions, LemmyClient};
+use serde::{Deserialize, Serialize};
 
-#[cfg(feature = "ssr")]
-pub fn get_internal_host() -> String {
-  std::env::var("LEMMY_UI_LEPTOS_LEMMY_HOST").unwrap_or_else(|_| LEMMY_UI_LEPTOS_LEMMY_HOST.into())
-}
+/// name of the meta tag used to hand the server's api version to the browser. The rest of the
+/// client options stays on the server, the browser reaches lemmy through its proxy.
+pub const API_VERSION_META: &str = "lemmy-api-version";
 
-#[cfg(not(feature = "ssr"))]
-pub fn get_external_host() -> String {
-  if let Some(s) = option_env!("LEMMY_UI_LEPTOS_LEMMY_HOST") {
-    s.into()
-  } else {
-    LEMMY_UI_LEPTOS_LEMMY_HOST.into()
-  }
+/// Where and how `LemmyClient` reaches the Lemmy backend. Decided by the server at runtime so that
+/// one WASM bundle can be deployed against any backend.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientOptions {
+  pub domain: String,
+  pub secure: bool,
+  pub api_version: String,
 }
 
-pub fn get_host() -> String {
+/// Options for the current render: on the server those the actix binary built from `AppConfig`,
+/// in the browser the compile time defaults with the api version the server wrote into the page.
+pub fn use_client_options() -> ClientOptions {
   cfg_if! {
       if #[cfg(feature="ssr")] {
-        get_internal_host()
+        leptos::expect_context::<ClientOptions>()
       } else {
-        get_external_host()
-      }
-  }
-}
-
-pub fn get_https() -> String {
-  cfg_if! {
-      if #[cfg(feature="ssr")] {
-        std::env::var("LEMMY_UI_LEPTOS_LEMMY_HTTPS").unwrap_or(format!("{LEMMY_UI_LEPTOS_LEMMY_HTTPS}"))
-      } else {
-        if let Some(s) = option_env!("LEMMY_UI_LEPTOS_LEMMY_HTTPS") {
-          s.into()
-        } else {
-          format!("{LEMMY_UI_LEPTOS_LEMMY_HTTPS}")
+        // the hydrated client goes through the proxy of the server, so only the api version counts
+        ClientOptions {
+          domain: LEMMY_UI_LEPTOS_LEMMY_HOST.into(),
+          secure: LEMMY_UI_LEPTOS_LEMMY_HTTPS,
+          api_version: read_page_api_version()
+            .unwrap_or_else(|| LEMMY_UI_LEPTOS_LEMMY_API_VERSION.into()),
         }
       }
   }
 }
 
-// maybe for use with lemmy client
-// fn should_use_https() -> bool {
-//   let https_env_var;
-//   cfg_if! {
-//       if #[cfg(feature="ssr")] {
-//         https_env_var = std::env::var("LEMMY_UI_HTTPS");
-//       } else {
-//         https_env_var = option_env!("LEMMY_UI_HTTPS");
-//       }
-//   }
-
-//   https_env_var.map_or(LEMMY_UI_LEPTOS_LEMMY_HTTPS, |var| var == "true")
-// }
-
-// pub fn get_client() -> LemmyClient {
-//   LemmyClient::new(ClientOptions {
-//     domain: get_host(),
-//     secure: should_use_https(),
-//   })
-// }
+#[cfg(not(feature = "ssr"))]
+fn read_page_api_version() -> Option<String> {
+  leptos::document()
+    .query_selector(&format!("meta[name=\"{API_VERSION_META}\"]"))
+    .ok()??
+    .get_attribute("content")
+}
@@ -1,7 +1,12 @@
 use crate::{
+  config::use_public_config,
   cookie::get_cookie,
   errors::LemmyAppError,
-  ui::components::common::nav::{BottomNav, TopNav},
+  ui::components::common::{
+    error_page::routed_error_page,
+    nav::{BottomNav, TopNav},
+    toasts::Toasts,
+  },
 };
 use lemmy_api_common::site::GetSiteResponse;
 use leptos::*;
@@ -24,13 +29,17 @@ pub fn Layout(
   };
 
   let ui_theme = expect_context::<RwSignal<Option<String>>>();
+  let default_theme = use_public_config().ui.default_theme;
   let theme = create_resource(
     move || (),
-    move |()| async move {
-      let r = get_cookie("theme").await;
-      match r {
-        Ok(Some(o)) => o,
-        _ => "retro".to_string(),
+    move |()| {
+      let default_theme = default_theme.clone();
+      async move {
+        let r = get_cookie("theme").await;
+        match r {
+          Ok(Some(o)) => o,
+          _ => default_theme,
+        }
       }
     },
   );
@@ -52,10 +61,13 @@ pub fn Layout(
                   view! {
                     <div class="flex flex-col min-h-screen" data-theme=move || ui_theme.get()>
                       <TopNav site_signal/>
+                      <Toasts/>
                       <div class="w-full flex flex-col flex-grow">
                         <div class="sm:container sm:mx-auto">
                           <div class="w-full flex flex-col flex-grow p-6">
-                            <Outlet/>
+                            <ErrorBoundary fallback=routed_error_page>
+                              <Outlet/>
+                            </ErrorBoundary>
                           </div>
                         </div>
                       </div>
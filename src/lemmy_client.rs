@@ -1,12 +1,23 @@
 use crate::{
-  cookie::get_cookie,
   errors::{LemmyAppError, LemmyAppErrorType, LemmyAppResult},
-  host::{get_host, get_https},
+  host::{use_client_options, ClientOptions},
 };
 use cfg_if::cfg_if;
-use lemmy_api_common::{comment::*, community::*, person::*, post::*, site::*, LemmyErrorType};
+use core::time::Duration;
+use lemmy_api_common::{
+  comment::*,
+  community::*,
+  person::*,
+  post::*,
+  private_message::*,
+  site::*,
+  LemmyErrorType,
+  SuccessResponse,
+};
 use leptos::Serializable;
 use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use tracing::Instrument;
 
 #[derive(Clone)]
 pub enum HttpType {
@@ -16,11 +27,163 @@ pub enum HttpType {
   Post,
   #[allow(dead_code)]
   Put,
+  #[allow(dead_code)]
+  Delete,
+}
+
+impl HttpType {
+  fn as_str(&self) -> &'static str {
+    match self {
+      HttpType::Get => "GET",
+      HttpType::Post => "POST",
+      HttpType::Put => "PUT",
+      HttpType::Delete => "DELETE",
+    }
+  }
+}
+
+/// header carrying the id of a request, so ui and backend logs can be matched up
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+// form fields that must never show up in logs
+const REDACTED_FIELDS: [&str; 12] = [
+  "jwt",
+  "auth",
+  "password",
+  "password_verify",
+  "old_password",
+  "new_password",
+  "new_password_verify",
+  "totp_2fa_token",
+  "totp_token",
+  "token",
+  "email",
+  "q",
+];
+
+// applies to both backends so that a hung lemmy surfaces as an error rather than a spinner
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// name of the meta tag carrying the responses fetched during SSR to the hydrating browser
+pub const TRANSFER_CACHE_META: &str = "lemmy-transfer-cache";
+
+/// GET response bodies keyed by request. Filled while rendering on the server, written into the
+/// page and then used once by the hydrating client, so first paint does not fetch everything twice.
+#[derive(Clone, Default)]
+pub struct TransferCache(Rc<RefCell<HashMap<String, String>>>);
+
+impl TransferCache {
+  fn key<T: Serialize>(path: &str, form: &T) -> String {
+    format!(
+      "{}?{}",
+      path,
+      serde_urlencoded::to_string(form).unwrap_or_default()
+    )
+  }
+
+  #[cfg(feature = "ssr")]
+  fn insert(&self, key: String, body: String) {
+    self.0.borrow_mut().insert(key, body);
+  }
+
+  #[cfg(not(feature = "ssr"))]
+  fn take(&self, key: &str) -> Option<String> {
+    self.0.borrow_mut().remove(key)
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(&*self.0.borrow()).unwrap_or_default()
+  }
+
+  pub fn from_json(json: &str) -> Self {
+    Self(Rc::new(RefCell::new(
+      serde_json::from_str(json).unwrap_or_default(),
+    )))
+  }
+}
+
+/// An empty cache to fill on the server, or the one the server wrote into the page.
+pub fn use_transfer_cache() -> TransferCache {
+  cfg_if! {
+      if #[cfg(feature = "hydrate")] {
+        leptos::document()
+          .query_selector(&format!("meta[name=\"{TRANSFER_CACHE_META}\"]"))
+          .ok()
+          .flatten()
+          .and_then(|meta| meta.get_attribute("content"))
+          .map(|json| TransferCache::from_json(&json))
+          .unwrap_or_default()
+      } else {
+        TransferCache::default()
+      }
+  }
+}
+
+#[derive(Clone)]
+pub struct LemmyClient {
+  options: ClientOptions,
+  transfer_cache: Option<TransferCache>,
+  retry_policy: RetryPolicy,
+}
+
+impl LemmyClient {
+  pub fn new(options: ClientOptions) -> Self {
+    Self {
+      options,
+      transfer_cache: None,
+      retry_policy: RetryPolicy::default(),
+    }
+  }
+
+  /// A copy of this client that retries according to `retry_policy`.
+  pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> Self {
+    Self {
+      retry_policy,
+      ..self
+    }
+  }
+
+  pub fn with_transfer_cache(self, transfer_cache: TransferCache) -> Self {
+    Self {
+      transfer_cache: Some(transfer_cache),
+      ..self
+    }
+  }
+
+  pub fn options(&self) -> &ClientOptions {
+    &self.options
+  }
+
+  fn build_route(&self, route: &str) -> String {
+    cfg_if! {
+        if #[cfg(feature = "hydrate")] {
+          // same origin, the actix server proxies the api to the internal host
+          format!("/api/{}/{}", self.options.api_version, route)
+        } else {
+          format!(
+            "http{}://{}/api/{}/{}",
+            if self.options.secure { "s" } else { "" },
+            self.options.domain,
+            self.options.api_version,
+            route
+          )
+        }
+    }
+  }
+}
+
+/// The client provided by `App`, or one built from the runtime options where there is no component
+/// tree (server functions).
+pub fn use_lemmy_client() -> LemmyClient {
+  leptos::use_context::<LemmyClient>().unwrap_or_else(|| LemmyClient::new(use_client_options()))
 }
 
 pub struct LemmyRequest<R: Serialize> {
   pub body: Option<R>,
+  /// sent instead of the session cookie's, on the server only
   pub jwt: Option<String>,
+  /// overrides the client's retry policy for this request only
+  pub retry_policy: Option<RetryPolicy>,
 }
 
 impl<R: Serialize> From<R> for LemmyRequest<R> {
@@ -28,10 +191,83 @@ impl<R: Serialize> From<R> for LemmyRequest<R> {
     LemmyRequest {
       body: Some(body),
       jwt: None,
+      retry_policy: None,
+    }
+  }
+}
+
+/// How often a failed request is tried again. Only failures that may go away by themselves (rate
+/// limiting, timeouts, an unavailable backend) are retried, and by default only for GET requests,
+/// as repeating anything else could apply it twice.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+  pub max_retries: u32,
+  pub base_delay: Duration,
+  pub max_delay: Duration,
+  pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+  fn default() -> Self {
+    Self {
+      max_retries: 2,
+      base_delay: Duration::from_millis(250),
+      max_delay: Duration::from_secs(5),
+      retry_non_idempotent: false,
     }
   }
 }
 
+impl RetryPolicy {
+  pub fn none() -> Self {
+    Self {
+      max_retries: 0,
+      ..Self::default()
+    }
+  }
+
+  /// Time to wait before the next attempt, or `None` to give up. A `Retry-After` from the server
+  /// wins over exponential backoff with full jitter.
+  fn delay(
+    &self,
+    method: &HttpType,
+    error: &LemmyAppError,
+    retry_after: Option<Duration>,
+    attempt: u32,
+  ) -> Option<Duration> {
+    let idempotent = matches!(method, HttpType::Get);
+    let transient = matches!(
+      error.error_type,
+      LemmyAppErrorType::RateLimited
+        | LemmyAppErrorType::Timeout
+        | LemmyAppErrorType::ServiceUnavailable
+    );
+
+    if attempt >= self.max_retries || !transient || !(idempotent || self.retry_non_idempotent) {
+      return None;
+    }
+
+    match retry_after {
+      Some(retry_after) if retry_after > self.max_delay => None,
+      Some(retry_after) => Some(retry_after),
+      None => {
+        let ceiling = self
+          .base_delay
+          .saturating_mul(2u32.saturating_pow(attempt))
+          .min(self.max_delay);
+        Some(ceiling.mul_f64(jitter()))
+      }
+    }
+  }
+}
+
+/// what a backend hands back before any lemmy specific interpretation
+struct RawResponse {
+  status: u16,
+  retry_after: Option<Duration>,
+  body: Vec<u8>,
+}
+
 mod private_trait {
   use super::{HttpType, LemmyRequest};
   use crate::errors::LemmyAppResult;
@@ -52,17 +288,134 @@ mod private_trait {
   }
 }
 
+impl private_trait::PrivateFetch for LemmyClient {
+  async fn make_request<Response, Form, Request>(
+    &self,
+    method: HttpType,
+    path: &str,
+    req: Request,
+  ) -> LemmyAppResult<Response>
+  where
+    Response: Serializable + for<'de> Deserialize<'de> + 'static,
+    Form: Serialize + core::clone::Clone + 'static + core::fmt::Debug,
+    Request: Into<LemmyRequest<Form>>,
+  {
+    let LemmyRequest {
+      body,
+      jwt,
+      retry_policy,
+    } = req.into();
+    let retry_policy = retry_policy.as_ref().unwrap_or(&self.retry_policy);
+
+    let cache_key = matches!(method, HttpType::Get).then(|| TransferCache::key(path, &body));
+
+    #[cfg(not(feature = "ssr"))]
+    if let (Some(cache), Some(key)) = (&self.transfer_cache, &cache_key) {
+      if let Some(cached) = cache.take(key) {
+        return decode_response(cached.as_bytes());
+      }
+    }
+
+    let request_id = format!("{:016x}", random_u64());
+    let span = tracing::info_span!(
+      "lemmy_request",
+      request_id = %request_id,
+      method = method.as_str(),
+      path,
+    );
+    tracing::debug!(parent: &span, form = %redact(&body), "sending");
+
+    let bytes = async {
+      let mut attempt = 0;
+      loop {
+        let started = now_millis();
+        let result = self.send(&method, path, &body, &jwt, &request_id).await;
+        // the wall clock may step back in between
+        let latency_ms = now_millis().saturating_sub(started);
+
+        let (error, retry_after) = match result {
+          Ok(raw) if raw.status < 400 => {
+            tracing::info!(status = raw.status, latency_ms, attempt, "response");
+            break Ok(raw.body);
+          }
+          Ok(raw) => {
+            tracing::warn!(status = raw.status, latency_ms, attempt, "error response");
+            (error_from_response(raw.status, &raw.body), raw.retry_after)
+          }
+          Err(e) => {
+            tracing::warn!(error = %e.error_type, latency_ms, attempt, "request failed");
+            (e, None)
+          }
+        };
+
+        match retry_policy.delay(&method, &error, retry_after, attempt) {
+          Some(delay) => {
+            tracing::info!(delay_ms = delay.as_millis() as u64, "retrying");
+            sleep(delay).await;
+            attempt += 1;
+          }
+          None => break Err(error),
+        }
+      }
+    }
+    .instrument(span)
+    .await?;
+
+    #[cfg(feature = "ssr")]
+    if let (Some(cache), Some(key)) = (&self.transfer_cache, cache_key) {
+      cache.insert(key, String::from_utf8_lossy(&bytes).into_owned());
+    }
+
+    decode_response(&bytes)
+  }
+}
+
+impl PublicFetch for LemmyClient {}
+
 pub trait PublicFetch: private_trait::PrivateFetch {
   async fn login(&self, form: Login) -> LemmyAppResult<LoginResponse> {
     self.make_request(HttpType::Post, "user/login", form).await
   }
 
+  async fn register(&self, form: Register) -> LemmyAppResult<LoginResponse> {
+    self
+      .make_request(HttpType::Post, "user/register", form)
+      .await
+  }
+
+  async fn get_captcha(&self) -> LemmyAppResult<GetCaptchaResponse> {
+    self
+      .make_request(HttpType::Get, "user/get_captcha", ())
+      .await
+  }
+
+  async fn password_reset(&self, form: PasswordReset) -> LemmyAppResult<SuccessResponse> {
+    self
+      .make_request(HttpType::Post, "user/password_reset", form)
+      .await
+  }
+
+  async fn password_change_after_reset(
+    &self,
+    form: PasswordChangeAfterReset,
+  ) -> LemmyAppResult<SuccessResponse> {
+    self
+      .make_request(HttpType::Post, "user/password_change", form)
+      .await
+  }
+
+  async fn verify_email(&self, form: VerifyEmail) -> LemmyAppResult<SuccessResponse> {
+    self
+      .make_request(HttpType::Post, "user/verify_email", form)
+      .await
+  }
+
   async fn logout(&self) -> LemmyAppResult<()> {
-    let _ = self
-      .make_request::<(), (), ()>(HttpType::Post, "user/logout", ())
-      .await;
-    // TODO: do not ignore error due to not being able to decode enpty http response cleanly
-    Ok(())
+    // older backends answer with an empty body, newer ones with a success response
+    self
+      .make_request::<Option<SuccessResponse>, (), ()>(HttpType::Post, "user/logout", ())
+      .await
+      .map(|_| ())
   }
 
   async fn list_communities(
@@ -74,10 +427,24 @@ pub trait PublicFetch: private_trait::PrivateFetch {
       .await
   }
 
+  async fn get_community(&self, form: GetCommunity) -> LemmyAppResult<GetCommunityResponse> {
+    self.make_request(HttpType::Get, "community", form).await
+  }
+
+  async fn follow_community(&self, form: FollowCommunity) -> LemmyAppResult<CommunityResponse> {
+    self
+      .make_request(HttpType::Post, "community/follow", form)
+      .await
+  }
+
   async fn get_comments(&self, form: GetComments) -> LemmyAppResult<GetCommentsResponse> {
     self.make_request(HttpType::Get, "comment/list", form).await
   }
 
+  async fn create_comment(&self, form: CreateComment) -> LemmyAppResult<CommentResponse> {
+    self.make_request(HttpType::Post, "comment", form).await
+  }
+
   async fn list_posts(&self, form: GetPosts) -> LemmyAppResult<GetPostsResponse> {
     self.make_request(HttpType::Get, "post/list", form).await
   }
@@ -90,31 +457,194 @@ pub trait PublicFetch: private_trait::PrivateFetch {
     self.make_request(HttpType::Get, "site", ()).await
   }
 
+  /// The site as seen by the owner of `jwt`, e.g. right after login when the request at hand does
+  /// not carry the session cookie yet.
+  async fn get_site_with_jwt(&self, jwt: String) -> LemmyAppResult<GetSiteResponse> {
+    let req = LemmyRequest {
+      body: Some(()),
+      jwt: Some(jwt),
+      retry_policy: None,
+    };
+    self
+      .make_request::<GetSiteResponse, (), _>(HttpType::Get, "site", req)
+      .await
+  }
+
+  async fn save_user_settings(&self, form: SaveUserSettings) -> LemmyAppResult<SuccessResponse> {
+    self
+      .make_request(HttpType::Put, "user/save_user_settings", form)
+      .await
+  }
+
+  async fn change_password(&self, form: ChangePassword) -> LemmyAppResult<LoginResponse> {
+    self
+      .make_request(HttpType::Put, "user/change_password", form)
+      .await
+  }
+
+  async fn generate_totp_secret(&self) -> LemmyAppResult<GenerateTotpSecretResponse> {
+    self
+      .make_request(HttpType::Post, "user/totp/generate", ())
+      .await
+  }
+
+  async fn update_totp(&self, form: UpdateTotp) -> LemmyAppResult<UpdateTotpResponse> {
+    self
+      .make_request(HttpType::Post, "user/totp/update", form)
+      .await
+  }
+
+  async fn search(&self, form: Search) -> LemmyAppResult<SearchResponse> {
+    self.make_request(HttpType::Get, "search", form).await
+  }
+
   async fn report_post(&self, form: CreatePostReport) -> LemmyAppResult<PostReportResponse> {
     self.make_request(HttpType::Post, "post/report", form).await
   }
 
+  async fn get_person_details(
+    &self,
+    form: GetPersonDetails,
+  ) -> LemmyAppResult<GetPersonDetailsResponse> {
+    self.make_request(HttpType::Get, "user", form).await
+  }
+
   async fn block_user(&self, form: BlockPerson) -> LemmyAppResult<BlockPersonResponse> {
     self.make_request(HttpType::Post, "user/block", form).await
   }
 
+  async fn get_unread_count(&self) -> LemmyAppResult<GetUnreadCountResponse> {
+    self
+      .make_request(HttpType::Get, "user/unread_count", ())
+      .await
+  }
+
+  async fn get_replies(&self, form: GetReplies) -> LemmyAppResult<GetRepliesResponse> {
+    self.make_request(HttpType::Get, "user/replies", form).await
+  }
+
+  async fn get_person_mentions(
+    &self,
+    form: GetPersonMentions,
+  ) -> LemmyAppResult<GetPersonMentionsResponse> {
+    self.make_request(HttpType::Get, "user/mention", form).await
+  }
+
+  async fn get_private_messages(
+    &self,
+    form: GetPrivateMessages,
+  ) -> LemmyAppResult<PrivateMessagesResponse> {
+    self
+      .make_request(HttpType::Get, "private_message/list", form)
+      .await
+  }
+
+  async fn mark_reply_as_read(
+    &self,
+    form: MarkCommentReplyAsRead,
+  ) -> LemmyAppResult<CommentReplyResponse> {
+    self
+      .make_request(HttpType::Post, "comment/mark_as_read", form)
+      .await
+  }
+
+  async fn mark_person_mention_as_read(
+    &self,
+    form: MarkPersonMentionAsRead,
+  ) -> LemmyAppResult<PersonMentionResponse> {
+    self
+      .make_request(HttpType::Post, "user/mention/mark_as_read", form)
+      .await
+  }
+
+  async fn mark_private_message_as_read(
+    &self,
+    form: MarkPrivateMessageAsRead,
+  ) -> LemmyAppResult<PrivateMessageResponse> {
+    self
+      .make_request(HttpType::Post, "private_message/mark_as_read", form)
+      .await
+  }
+
+  async fn create_private_message(
+    &self,
+    form: CreatePrivateMessage,
+  ) -> LemmyAppResult<PrivateMessageResponse> {
+    self
+      .make_request(HttpType::Post, "private_message", form)
+      .await
+  }
+
+  async fn edit_private_message(
+    &self,
+    form: EditPrivateMessage,
+  ) -> LemmyAppResult<PrivateMessageResponse> {
+    self
+      .make_request(HttpType::Put, "private_message", form)
+      .await
+  }
+
+  async fn delete_private_message(
+    &self,
+    form: DeletePrivateMessage,
+  ) -> LemmyAppResult<PrivateMessageResponse> {
+    self
+      .make_request(HttpType::Post, "private_message/delete", form)
+      .await
+  }
+
+  async fn report_private_message(
+    &self,
+    form: CreatePrivateMessageReport,
+  ) -> LemmyAppResult<PrivateMessageReportResponse> {
+    self
+      .make_request(HttpType::Post, "private_message/report", form)
+      .await
+  }
+
+  async fn mark_all_as_read(&self) -> LemmyAppResult<GetRepliesResponse> {
+    self
+      .make_request(HttpType::Post, "user/mark_all_as_read", ())
+      .await
+  }
+
+  async fn ban_person(&self, form: BanPerson) -> LemmyAppResult<BanPersonResponse> {
+    self.make_request(HttpType::Post, "user/ban", form).await
+  }
+
+  async fn purge_person(&self, form: PurgePerson) -> LemmyAppResult<SuccessResponse> {
+    self
+      .make_request(HttpType::Post, "admin/purge/person", form)
+      .await
+  }
+
   async fn save_post(&self, form: SavePost) -> LemmyAppResult<PostResponse> {
     self.make_request(HttpType::Put, "post/save", form).await
   }
 
   async fn like_post(&self, form: CreatePostLike) -> LemmyAppResult<PostResponse> {
-    self.make_request(HttpType::Post, "post/like", form).await
+    // a vote applied twice is a different vote, so never repeat it behind the user's back
+    let req = LemmyRequest {
+      body: Some(form),
+      jwt: None,
+      retry_policy: Some(RetryPolicy::none()),
+    };
+    self
+      .make_request::<PostResponse, CreatePostLike, _>(HttpType::Post, "post/like", req)
+      .await
   }
 }
 
 cfg_if! {
     if #[cfg(feature = "ssr")] {
 
-        use actix_web::web;
+        use crate::cookie::{get_cookie, AUTH_COOKIE};
+        use actix_web::{http::header, web};
         use awc::{Client, ClientRequest};
         use leptos_actix::{extract};
 
-        pub struct LemmyClient;
+        // post and comment lists are well above awc's default body limit
+        const RESPONSE_LIMIT: usize = 10 * 1024 * 1024;
 
         trait MaybeBearerAuth {
             fn maybe_bearer_auth(self, token: Option<impl core::fmt::Display>) -> Self;
@@ -130,25 +660,21 @@ cfg_if! {
             }
         }
 
-        impl private_trait::PrivateFetch for LemmyClient {
-            async fn make_request<Response, Form, Request>(
+        impl LemmyClient {
+            async fn send<Form: Serialize>(
                 &self,
-                method: HttpType,
+                method: &HttpType,
                 path: &str,
-                req: Request,
-            ) -> LemmyAppResult<Response>
-            where
-                Response: Serializable + for<'de> Deserialize<'de> + 'static,
-                Form: Serialize + core::clone::Clone + 'static + core::fmt::Debug,
-                Request: Into<LemmyRequest<Form>>,
-            {
-                let LemmyRequest {body, ..} = req.into();
-
-                let jwt = get_cookie("jwt").await?;
-
-                let route = build_route(path);
+                body: &Option<Form>,
+                jwt: &Option<String>,
+                request_id: &str,
+            ) -> LemmyAppResult<RawResponse> {
+                let jwt = match jwt {
+                    Some(jwt) => Some(jwt.clone()),
+                    None => get_cookie(AUTH_COOKIE).await?,
+                };
 
-                leptos::logging::log!("{}", format!("{}?{}", route, serde_urlencoded::to_string(&body).unwrap_or("".to_string())));
+                let route = self.build_route(path);
 
                 let client = extract::<web::Data<Client>>().await?;
 
@@ -156,82 +682,95 @@ cfg_if! {
                     HttpType::Get => client
                         .get(&route)
                         .maybe_bearer_auth(jwt.clone())
-                        .query(&body)?
+                        .insert_header((REQUEST_ID_HEADER, request_id))
+                        .timeout(REQUEST_TIMEOUT)
+                        .query(body)?
                         .send(),
                     HttpType::Post => client
                         .post(&route)
                         .maybe_bearer_auth(jwt.clone())
-                        .send_json(&body),
+                        .insert_header((REQUEST_ID_HEADER, request_id))
+                        .timeout(REQUEST_TIMEOUT)
+                        .send_json(body),
                     HttpType::Put => client
                         .put(&route)
                         .maybe_bearer_auth(jwt.clone())
-                        .send_json(&body)
+                        .insert_header((REQUEST_ID_HEADER, request_id))
+                        .timeout(REQUEST_TIMEOUT)
+                        .send_json(body),
+                    HttpType::Delete => client
+                        .delete(&route)
+                        .maybe_bearer_auth(jwt.clone())
+                        .insert_header((REQUEST_ID_HEADER, request_id))
+                        .timeout(REQUEST_TIMEOUT)
+                        .send_json(body)
                 }.await?;
 
-                match r.status().as_u16() {
-                    400..=599 => {
-                        let api_result = r.json::<LemmyErrorType>().await;
-
-                        match api_result {
-                            Ok(le) => {
-                              return Err(LemmyAppError{ error_type: LemmyAppErrorType::ApiError(le.clone()), content: format!("{:#?}", le) })
-                            },
-                            Err(e) => {
-                              return Err(LemmyAppError{ error_type: LemmyAppErrorType::Unknown, content: format!("{:#?}", e) })
-                            },
-                        }
-                    },
-                    _ => {
-                    },
-                };
-
-                r.json::<Response>().await.map_err(Into::into)
+                let retry_after = r
+                    .headers()
+                    .get(header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(parse_retry_after);
+
+                Ok(RawResponse {
+                    status: r.status().as_u16(),
+                    retry_after,
+                    body: r.body().limit(RESPONSE_LIMIT).await?.to_vec(),
+                })
             }
         }
 
-        impl PublicFetch for LemmyClient {}
-
-    } else {
-
-        use leptos::wasm_bindgen::UnwrapThrowExt;
-        use web_sys::AbortController;
-        use gloo_net::{http, http::RequestBuilder};
+        async fn sleep(duration: Duration) {
+            actix_web::rt::time::sleep(duration).await;
+        }
 
-        pub struct LemmyClient;
+        fn random_u64() -> u64 {
+            use std::{collections::hash_map::RandomState, hash::{BuildHasher, Hasher}};
 
-        trait MaybeBearerAuth {
-            fn maybe_bearer_auth(self, token: Option<&str>) -> Self;
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u128(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos());
+            hasher.finish()
         }
 
-        impl MaybeBearerAuth for RequestBuilder {
-           fn maybe_bearer_auth(self, token: Option<&str>) -> Self {
-                if let Some(token) = token {
-                    self.header("Authorization", format!("Bearer {token}").as_str())
-                } else {
-                    self
-                }
-            }
+        pub(crate) fn now_millis() -> u64 {
+            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis() as u64
         }
 
-        impl private_trait::PrivateFetch for LemmyClient {
-            async fn make_request<Response, Form, Request>(
+    } else {
+
+        use leptos::wasm_bindgen::UnwrapThrowExt;
+        use std::cell::Cell;
+        use web_sys::AbortController;
+        use gloo_net::http;
+
+        impl LemmyClient {
+            async fn send<Form: Serialize>(
                 &self,
-                method: HttpType,
+                method: &HttpType,
                 path: &str,
-                req: Request,
-            ) -> LemmyAppResult<Response>
-            where
-                Response: Serializable + for<'de> Deserialize<'de> + 'static,
-                Form: Serialize + core::clone::Clone + 'static + core::fmt::Debug,
-                Request: Into<LemmyRequest<Form>>,
-            {
-                let LemmyRequest { body, .. } = req.into();
-                let route = &build_route(path);
-
-                let jwt = get_cookie("jwt").await?;
+                body: &Option<Form>,
+                _jwt: &Option<String>,
+                request_id: &str,
+            ) -> LemmyAppResult<RawResponse> {
+                // no bearer token, the jwt cookie is out of reach of scripts and the proxy adds it
+                let route = &self.build_route(path);
 
                 let abort_controller = AbortController::new().ok();
                 let abort_signal = abort_controller.as_ref().map(AbortController::signal);
+
+                let timed_out = Rc::new(Cell::new(false));
+                let timeout = abort_controller.clone().and_then(|abort_controller| {
+                    let timed_out = timed_out.clone();
+                    leptos::set_timeout_with_handle(
+                        move || {
+                            timed_out.set(true);
+                            abort_controller.abort();
+                        },
+                        REQUEST_TIMEOUT,
+                    )
+                    .ok()
+                });
+
                 leptos::on_cleanup( move || {
                     if let Some(abort_controller) = abort_controller {
                         abort_controller.abort()
@@ -240,58 +779,130 @@ cfg_if! {
 
                 let r = match method {
                     HttpType::Get => http::Request::
-                        get(&build_fetch_query(path, body))
-                        .maybe_bearer_auth(jwt.as_deref())
+                        get(&build_fetch_query(route, body))
+                        .header(REQUEST_ID_HEADER, request_id)
                         .abort_signal(abort_signal.as_ref())
                         .build()
                         .expect_throw("Could not parse query params"),
                     HttpType::Post => http::Request::post(route)
-                        .maybe_bearer_auth(jwt.as_deref())
+                        .header(REQUEST_ID_HEADER, request_id)
                         .abort_signal(abort_signal.as_ref())
-                        .json(&body)
+                        .json(body)
                         .expect_throw("Could not parse json body"),
                     HttpType::Put => http::Request::put(route)
-                        .maybe_bearer_auth(jwt.as_deref())
+                        .header(REQUEST_ID_HEADER, request_id)
                         .abort_signal(abort_signal.as_ref())
-                        .json(&body)
+                        .json(body)
+                        .expect_throw("Could not parse json body"),
+                    HttpType::Delete => http::Request::delete(route)
+                        .header(REQUEST_ID_HEADER, request_id)
+                        .abort_signal(abort_signal.as_ref())
+                        .json(body)
                         .expect_throw("Could not parse json body")
-                }.send().await?;
-
-                match r.status() {
-                    400..=599 => {
-                        let api_result = r.json::<LemmyErrorType>().await;
-                        match api_result {
-                            Ok(le) => {
-                                return Err(LemmyAppError{ error_type: LemmyAppErrorType::ApiError(le.clone()), content: format!("{:#?}", le) })
-                            },
-                            Err(e) => {
-                                return Err(LemmyAppError{ error_type: LemmyAppErrorType::Unknown, content: format!("{:#?}", e) })
-                            },
-                        }
-                    },
-                    _ => {
-                    },
-                };
+                }.send().await.map_err(|e| fetch_error(e, timed_out.get()))?;
+
+                let bytes = r.binary().await.map_err(|e| fetch_error(e, timed_out.get()))?;
 
-                r.json::<Response>().await.map_err(Into::into)
+                if let Some(timeout) = timeout {
+                    timeout.clear();
+                }
+
+                Ok(RawResponse {
+                    status: r.status(),
+                    retry_after: r.headers().get("retry-after").as_deref().and_then(parse_retry_after),
+                    body: bytes,
+                })
             }
         }
 
-        impl PublicFetch for LemmyClient {}
+        async fn sleep(duration: Duration) {
+            gloo_timers::future::sleep(duration).await;
+        }
+
+        fn random_u64() -> u64 {
+            (web_sys::js_sys::Math::random() * u64::MAX as f64) as u64
+        }
+
+        pub(crate) fn now_millis() -> u64 {
+            web_sys::js_sys::Date::now() as u64
+        }
+
+        fn fetch_error(error: gloo_net::Error, timed_out: bool) -> LemmyAppError {
+            match error {
+                e if timed_out => LemmyAppError {
+                    error_type: LemmyAppErrorType::Timeout,
+                    content: format!("{:#?}", e),
+                },
+                // fetch rejects with a TypeError when the server cannot be reached at all
+                gloo_net::Error::JsError(ref e) if e.name == "TypeError" => LemmyAppError {
+                    error_type: LemmyAppErrorType::ServiceUnavailable,
+                    content: format!("{:#?}", e),
+                },
+                e => e.into(),
+            }
+        }
 
-        fn build_fetch_query<T: Serialize>(path: &str, form: T) -> String {
+        fn build_fetch_query<T: Serialize>(route: &str, form: T) -> String {
             let form_str = serde_urlencoded::to_string(&form).unwrap_or("".to_string());
-            format!("{}?{}", build_route(path), form_str)
+            format!("{}?{}", route, form_str)
         }
 
     }
 }
 
-fn build_route(route: &str) -> String {
-  format!(
-    "http{}://{}/api/v3/{}",
-    if get_https() == "true" { "s" } else { "" },
-    get_host(),
-    route
-  )
+fn jitter() -> f64 {
+  (random_u64() % 1_000) as f64 / 1_000.0
+}
+
+/// The form as json with secrets and search terms blanked out.
+fn redact<T: Serialize>(form: &T) -> String {
+  match serde_json::to_value(form) {
+    Ok(serde_json::Value::Object(mut fields)) => {
+      for (name, value) in fields.iter_mut() {
+        if REDACTED_FIELDS.contains(&name.as_str()) && !value.is_null() {
+          *value = "[redacted]".into();
+        }
+      }
+      serde_json::Value::Object(fields).to_string()
+    }
+    Ok(value) => value.to_string(),
+    Err(_) => String::default(),
+  }
+}
+
+// lemmy and nginx send delay seconds rather than an http date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+  value.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+/// Maps a failed response onto an error type that tells apart a refused request, a missing object
+/// and a backend that is not there.
+fn error_from_response(status: u16, body: &[u8]) -> LemmyAppError {
+  let api_error = serde_json::from_slice::<LemmyErrorType>(body).ok();
+
+  let error_type = match (status, api_error) {
+    (429, _) | (_, Some(LemmyErrorType::RateLimitError)) => LemmyAppErrorType::RateLimited,
+    (_, Some(le)) => LemmyAppErrorType::ApiError(le),
+    (401, None) => LemmyAppErrorType::Unauthorized,
+    (403, None) => LemmyAppErrorType::Forbidden,
+    (404, None) => LemmyAppErrorType::NotFound,
+    (408 | 504, None) => LemmyAppErrorType::Timeout,
+    (502 | 503, None) => LemmyAppErrorType::ServiceUnavailable,
+    (500..=599, None) => LemmyAppErrorType::InternalServerError,
+    (_, None) => LemmyAppErrorType::Unknown,
+  };
+
+  LemmyAppError {
+    error_type,
+    content: format!("{} {}", status, String::from_utf8_lossy(body)),
+  }
+}
+
+fn decode_response<Response>(body: &[u8]) -> LemmyAppResult<Response>
+where
+  Response: for<'de> Deserialize<'de>,
+{
+  // unit responses come back without a body
+  let body: &[u8] = if body.is_empty() { b"null" } else { body };
+  serde_json::from_slice::<Response>(body).map_err(Into::into)
 }
@@ -1,24 +1,50 @@
 // useful in development to only have errors in compiler output
 // #![allow(warnings)]
 
-mod config;
-mod cookie;
-mod errors;
+pub mod config;
+pub mod cookie;
+pub mod errors;
+pub mod flash;
 pub mod host;
 mod layout;
-mod lemmy_client;
+pub mod lemmy_client;
+mod notifications;
+#[cfg(feature = "ssr")]
+pub mod proxy;
+pub mod redirect;
+#[cfg(feature = "ssr")]
+pub mod server;
 mod ui;
 
 use crate::{
-  errors::LemmyAppError,
+  config::{use_public_config, PUBLIC_CONFIG_META},
+  errors::{LemmyAppError, LemmyAppErrorType},
+  flash::{provide_flash, FLASH_META},
+  host::{use_client_options, API_VERSION_META},
   i18n::*,
   layout::Layout,
   lemmy_client::*,
+  notifications::provide_notifications,
   ui::components::{
+    common::{error_page::ErrorPage, nav::provide_unread_count},
     communities::communities_activity::CommunitiesActivity,
+    community::community_activity::CommunityActivity,
     home::home_activity::HomeActivity,
+    inbox::inbox_activity::InboxActivity,
     login::login_activity::LoginActivity,
+    password_reset::{
+      password_change_activity::PasswordChangeActivity,
+      password_reset_activity::PasswordResetActivity,
+    },
+    person::profile_activity::ProfileActivity,
     post::post_activity::PostActivity,
+    private_message::{
+      conversation_activity::ConversationActivity,
+      conversations_activity::ConversationsActivity,
+    },
+    settings::settings_activity::SettingsActivity,
+    signup::signup_activity::SignupActivity,
+    verify_email::verify_email_activity::VerifyEmailActivity,
   },
 };
 use lemmy_api_common::site::GetSiteResponse;
@@ -33,8 +59,15 @@ pub fn App() -> impl IntoView {
   provide_meta_context();
   provide_i18n_context();
 
-  let error = create_rw_signal::<Option<LemmyAppError>>(None);
-  provide_context(error);
+  let client_options = use_client_options();
+  let transfer_cache = use_transfer_cache();
+  let client = LemmyClient::new(client_options.clone()).with_transfer_cache(transfer_cache.clone());
+  provide_context(client.clone());
+  let client = store_value(client);
+
+  let notifications = provide_notifications();
+  let flash = provide_flash();
+  provide_unread_count();
   let user = create_rw_signal::<Option<bool>>(None);
   provide_context(user);
   let ui_theme = create_rw_signal::<Option<String>>(None);
@@ -51,18 +84,18 @@ pub fn App() -> impl IntoView {
           s.my_user = None;
           Ok(s)
         } else {
-          LemmyClient.get_site().await
+          client.get_value().get_site().await
         }
       } else {
-        LemmyClient.get_site().await
+        client.get_value().get_site().await
       };
 
-      // let result = LemmyClient.get_site().await;
+      // let result = client.get_value().get_site().await;
 
       match result {
         Ok(o) => Ok(o),
         Err(e) => {
-          error.set(Some(e.clone()));
+          notifications.error(e.clone());
           Err(e)
         }
       }
@@ -70,6 +103,17 @@ pub fn App() -> impl IntoView {
   );
 
   view! {
+    // hands the server's runtime api version to the hydrating browser, but not where lemmy is
+    <Meta name=API_VERSION_META content=client_options.api_version.clone()/>
+    // and the part of its config that the browser needs as well
+    <Meta
+      name=PUBLIC_CONFIG_META
+      content=serde_json::to_string(&use_public_config()).unwrap_or_default()
+    />
+    // the error of a form posted without javascript, see `Flash`
+    <Meta name=FLASH_META content=flash.to_json()/>
+    // read when the head is rendered, after every resource has resolved
+    <Meta name=TRANSFER_CACHE_META content=move || transfer_cache.to_json()/>
     <Transition fallback=|| {}>
       {move || {
           ssr_site
@@ -87,39 +131,52 @@ pub fn App() -> impl IntoView {
 
           <Route path="" view=move || view! { <HomeActivity site_signal/> }/>
 
-          <Route path="create_post" view=CommunitiesActivity/>
+          <Route path="create_post" view=Placeholder/>
           <Route path="post/:id" view=PostActivity/>
 
-          <Route path="search" view=CommunitiesActivity/>
+          <Route path="search" view=Placeholder/>
           <Route path="communities" view=CommunitiesActivity/>
-          <Route path="create_community" view=CommunitiesActivity/>
-          <Route path="c/:id" view=CommunitiesActivity/>
+          <Route path="create_community" view=Placeholder/>
+          <Route path="c/:name" view=CommunityActivity/>
 
           <Route path="login" view=LoginActivity/>
-          <Route path="logout" view=CommunitiesActivity/>
-          <Route path="signup" view=CommunitiesActivity/>
-
-          <Route path="inbox" view=CommunitiesActivity/>
-          <Route path="settings" view=CommunitiesActivity/>
-          <Route path="u/:id" view=CommunitiesActivity/>
-
-          <Route path="modlog" view=CommunitiesActivity/>
-          <Route path="instances" view=CommunitiesActivity/>
+          <Route path="logout" view=Placeholder/>
+          <Route path="signup" view=move || view! { <SignupActivity site_signal/> }/>
+          <Route path="password_reset" view=PasswordResetActivity/>
+          <Route path="password_change/:token" view=PasswordChangeActivity/>
+          <Route path="verify_email/:token" view=VerifyEmailActivity/>
+
+          <Route path="inbox" view=move || view! { <InboxActivity site_signal/> }/>
+          <Route path="settings" view=move || view! { <SettingsActivity site_signal/> }/>
+          <Route path="u/:name" view=move || view! { <ProfileActivity site_signal/> }/>
+          <Route path="messages" view=move || view! { <ConversationsActivity site_signal/> }/>
+          <Route path="messages/:id" view=move || view! { <ConversationActivity site_signal/> }/>
+          <Route
+            path="create_private_message/:id"
+            view=move || view! { <ConversationActivity site_signal/> }
+          />
+
+          <Route path="modlog" view=Placeholder/>
+          <Route path="instances" view=Placeholder/>
         </Route>
       </Routes>
     </Router>
   }
 }
 
+// for the pages that are still to be written
 #[component]
-fn NotFound() -> impl IntoView {
-  #[cfg(feature = "ssr")]
-  {
-    let resp = expect_context::<leptos_actix::ResponseOptions>();
-    resp.set_status(actix_web::http::StatusCode::NOT_FOUND);
+fn Placeholder() -> impl IntoView {
+  view! {
+    <main class="mx-auto">
+      <h2 class="p-6 text-4xl">"Coming soon"</h2>
+    </main>
   }
+}
 
-  view! { <h1>"Not Found"</h1> }
+#[component]
+fn NotFound() -> impl IntoView {
+  view! { <ErrorPage error=LemmyAppErrorType::NotFound.into()/> }
 }
 
 #[cfg(feature = "hydrate")]
@@ -7,56 +7,34 @@ use leptos::*;
 
 cfg_if! {
     if #[cfg(feature = "ssr")] {
-        use actix_files::Files;
         use actix_web::*;
 
-        use leptos_actix::{generate_route_list, LeptosRoutes};
-        use awc::Client;
-
-        #[actix_web::get("favicon.svg")]
-        async fn favicon(
-            leptos_options: web::Data<leptos::LeptosOptions>,
-        ) -> actix_web::Result<actix_files::NamedFile> {
-            let leptos_options = leptos_options.into_inner();
-            let site_root = &leptos_options.site_root;
-            Ok(actix_files::NamedFile::open(format!("{site_root}/favicon.svg"))?)
-        }
-
-        #[actix_web::get("icons.svg")]
-        async fn icons(
-            leptos_options: web::Data<leptos::LeptosOptions>
-        ) -> actix_web::Result<actix_files::NamedFile> {
-            let leptos_options = leptos_options.into_inner();
-            let site_root = &leptos_options.site_root;
-            Ok(actix_files::NamedFile::open(format!("{site_root}/icons.svg"))?)
-        }
+        use leptos_actix::generate_route_list;
+        use lemmy_ui_leptos::{config::AppConfig, server};
+        use tracing_subscriber::EnvFilter;
 
         #[actix_web::main]
         async fn main() -> std::io::Result<()> {
+            // e.g. LEMMY_UI_LEPTOS_LOG=lemmy_ui_leptos=debug,info
+            tracing_subscriber::fmt()
+                .with_env_filter(
+                    EnvFilter::try_from_env("LEMMY_UI_LEPTOS_LOG")
+                        .unwrap_or_else(|_| EnvFilter::new("info")),
+                )
+                .init();
+
             let conf = get_configuration(None).await.unwrap();
             let addr = conf.leptos_options.site_addr;
             let routes = generate_route_list(App);
+            // read once at startup and handed to every render and server function
+            let config = AppConfig::load()?;
 
             HttpServer::new(move || {
-                let leptos_options = &conf.leptos_options;
-                let site_root = &leptos_options.site_root;
-                let routes = &routes;
-
-                let client = web::Data::new(Client::new());
-
-                App::new()
-                    .route("/serverfn/{tail:.*}", leptos_actix::handle_server_fns())
-                    .service(Files::new("/pkg", format!("{site_root}/pkg")))
-                    .service(Files::new("/assets", site_root))
-                    .service(favicon)
-                    .service(icons)
-                    .leptos_routes(
-                        leptos_options.to_owned(),
-                        routes.to_owned(),
-                        App
-                    )
-                    .app_data(web::Data::new(leptos_options.to_owned()))
-                    .app_data(client)
+                App::new().configure(server::configure(
+                    conf.leptos_options.to_owned(),
+                    routes.to_owned(),
+                    config.to_owned(),
+                ))
             })
             .bind(&addr)?
             .run()
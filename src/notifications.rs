@@ -0,0 +1,150 @@
+use crate::errors::LemmyAppError;
+use core::time::Duration;
+use leptos::*;
+
+// more than this and the oldest make room
+const MAX_NOTIFICATIONS: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+  Info,
+  Success,
+  Error,
+}
+
+impl Severity {
+  /// How long a notification stays up unless dismissed earlier. Errors stay until dismissed.
+  pub fn auto_dismiss(&self) -> Option<Duration> {
+    match self {
+      Severity::Info | Severity::Success => Some(Duration::from_secs(5)),
+      Severity::Error => None,
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub enum NotificationMessage {
+  /// turned into localized text when shown
+  Error(LemmyAppError),
+  Text(String),
+}
+
+impl PartialEq for NotificationMessage {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (NotificationMessage::Error(a), NotificationMessage::Error(b)) => {
+        a.error_type == b.error_type && a.content == b.content
+      }
+      (NotificationMessage::Text(a), NotificationMessage::Text(b)) => a == b,
+      _ => false,
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct Notification {
+  pub id: u64,
+  pub severity: Severity,
+  pub message: NotificationMessage,
+  /// how often the same message was pushed while this one was showing
+  pub count: u32,
+  // bumped on every repeat, so that only the latest timer dismisses
+  generation: u32,
+}
+
+/// The queue of messages shown as toasts by `Layout`. Provided by `App`, and fed by components
+/// with errors from `LemmyClient` as well as confirmations of what the user just did.
+#[derive(Clone, Copy)]
+pub struct Notifications {
+  queue: RwSignal<Vec<Notification>>,
+  next_id: StoredValue<u64>,
+}
+
+impl Notifications {
+  fn new() -> Self {
+    Self {
+      queue: create_rw_signal(vec![]),
+      next_id: store_value(0),
+    }
+  }
+
+  pub fn list(&self) -> Signal<Vec<Notification>> {
+    self.queue.into()
+  }
+
+  pub fn error(&self, error: LemmyAppError) {
+    self.push(Severity::Error, NotificationMessage::Error(error));
+  }
+
+  pub fn success(&self, text: impl Into<String>) {
+    self.push(Severity::Success, NotificationMessage::Text(text.into()));
+  }
+
+  pub fn info(&self, text: impl Into<String>) {
+    self.push(Severity::Info, NotificationMessage::Text(text.into()));
+  }
+
+  /// Adds a notification, or brings back up one with the same message that is still showing.
+  pub fn push(&self, severity: Severity, message: NotificationMessage) {
+    let mut shown = None;
+
+    self.queue.update(|queue| {
+      if let Some(n) = queue
+        .iter_mut()
+        .find(|n| n.severity == severity && n.message == message)
+      {
+        n.count += 1;
+        n.generation += 1;
+        shown = Some((n.id, n.generation));
+      } else {
+        let id = self.next_id.get_value();
+        self.next_id.set_value(id + 1);
+
+        queue.push(Notification {
+          id,
+          severity,
+          message,
+          count: 1,
+          generation: 0,
+        });
+        if queue.len() > MAX_NOTIFICATIONS {
+          queue.remove(0);
+        }
+        shown = Some((id, 0));
+      }
+    });
+
+    if let (Some((id, generation)), Some(after)) = (shown, severity.auto_dismiss()) {
+      self.dismiss_later(id, generation, after);
+    }
+  }
+
+  pub fn dismiss(&self, id: u64) {
+    self.queue.update(|queue| queue.retain(|n| n.id != id));
+  }
+
+  #[cfg(not(feature = "ssr"))]
+  fn dismiss_later(&self, id: u64, generation: u32, after: Duration) {
+    let queue = self.queue;
+    set_timeout(
+      move || {
+        queue.update(|queue| queue.retain(|n| n.id != id || n.generation != generation));
+      },
+      after,
+    );
+  }
+
+  // rendered once on the server, the hydrated page takes over from there
+  #[cfg(feature = "ssr")]
+  fn dismiss_later(&self, _id: u64, _generation: u32, _after: Duration) {}
+}
+
+pub fn provide_notifications() -> Notifications {
+  let notifications = Notifications::new();
+  provide_context(notifications);
+  notifications
+}
+
+pub fn use_notifications() -> Notifications {
+  expect_context::<Notifications>()
+}
@@ -0,0 +1,102 @@
+use crate::{config::ProxyConfig, cookie::AUTH_COOKIE, host::ClientOptions};
+use actix_proxy::SendRequestError;
+use actix_web::{
+  http::header::{self, HeaderName},
+  web,
+  HttpRequest,
+  HttpResponse,
+};
+use awc::Client;
+
+// headers that only describe the hop between two peers and must not be forwarded
+const HOP_BY_HOP: [HeaderName; 8] = [
+  header::CONNECTION,
+  header::HOST,
+  header::PROXY_AUTHENTICATE,
+  header::PROXY_AUTHORIZATION,
+  header::TE,
+  header::TRAILER,
+  header::TRANSFER_ENCODING,
+  header::UPGRADE,
+];
+
+/// The client `forward` streams with. Apart from the one of server functions, as its timeout has to
+/// cover a whole upload rather than awc's default few seconds.
+pub struct ProxyClient(Client);
+
+impl ProxyClient {
+  pub fn new(config: &ProxyConfig) -> Self {
+    Self(Client::builder().timeout(config.timeout()).finish())
+  }
+}
+
+/// Streams a browser request for `/api/*` or `/pictrs/*` to the same path on the internal Lemmy
+/// host, so that the hydrated client can use relative urls and the backend stays private. The
+/// session's jwt is sent on as a bearer token, and the browser's address as `X-Forwarded-For`.
+pub async fn forward(
+  req: HttpRequest,
+  payload: web::Payload,
+  client: web::Data<ProxyClient>,
+  options: web::Data<ClientOptions>,
+  proxy: web::Data<ProxyConfig>,
+) -> Result<HttpResponse, SendRequestError> {
+  let url = format!(
+    "http{}://{}{}",
+    if options.secure { "s" } else { "" },
+    options.domain,
+    req
+      .uri()
+      .path_and_query()
+      .map(|p| p.as_str())
+      .unwrap_or_else(|| req.uri().path())
+  );
+
+  // keeps method, auth and content headers, while leaving the body untouched
+  let mut upstream = client.0.request_from(url, req.head()).no_decompress();
+  for name in HOP_BY_HOP.iter() {
+    upstream.headers_mut().remove(name);
+  }
+
+  // the browser cannot read its HttpOnly jwt cookie, so the token is attached here. Other ui cookies
+  // are none of lemmy's business
+  upstream.headers_mut().remove(header::COOKIE);
+  if let Some(jwt) = req.cookie(AUTH_COOKIE) {
+    if !upstream.headers().contains_key(header::AUTHORIZATION) {
+      upstream = upstream.bearer_auth(jwt.value());
+    }
+  }
+
+  // lemmy rate limits by client address, so it needs the browser's rather than ours. Only a trusted
+  // proxy in front gets to name it, as a browser could name any address in its own headers
+  {
+    let peer = req.peer_addr().map(|addr| addr.ip());
+    let info = req.connection_info();
+    let (ip, scheme) = if peer.is_some_and(|ip| proxy.trusted_proxies.contains(&ip)) {
+      (info.realip_remote_addr().map(String::from), info.scheme())
+    } else {
+      let scheme = if req.app_config().secure() {
+        "https"
+      } else {
+        "http"
+      };
+      (peer.map(|ip| ip.to_string()), scheme)
+    };
+    if let Some(ip) = ip {
+      upstream = upstream
+        .insert_header(("X-Real-IP", ip.as_str()))
+        .insert_header((header::X_FORWARDED_FOR, ip.as_str()));
+    }
+    upstream = upstream.insert_header((header::X_FORWARDED_PROTO, scheme));
+  }
+
+  let res = upstream.send_stream(payload).await?;
+
+  let mut response = HttpResponse::build(res.status());
+  for (name, value) in res.headers().iter() {
+    if !HOP_BY_HOP.contains(name) {
+      response.append_header((name.clone(), value.clone()));
+    }
+  }
+
+  Ok(response.streaming(res))
+}
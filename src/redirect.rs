@@ -0,0 +1,83 @@
+use leptos::SignalGet;
+
+/// The path a form posted without javascript may send the browser back to, or `None` for anything
+/// that would lead off this site. Takes a local path like `/post/42?sort=New`, or an absolute url on
+/// `host` such as a Referer. Any fragment is dropped, the caller adds its own anchor.
+pub fn local_path(target: &str, host: &str) -> Option<String> {
+  let target = target.trim();
+  let target = target.split('#').next().unwrap_or_default();
+
+  let path = if target.starts_with('/') {
+    target.to_string()
+  } else {
+    let rest = target
+      .strip_prefix("https://")
+      .or_else(|| target.strip_prefix("http://"))?;
+    let end = rest.find(['/', '?']).unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(end);
+
+    if !authority.eq_ignore_ascii_case(host) {
+      return None;
+    }
+
+    if path.starts_with('/') {
+      path.to_string()
+    } else {
+      format!("/{path}")
+    }
+  };
+
+  // browsers read `//evil.example` and `/\evil.example` as another host
+  if path.starts_with("//") || path.contains('\\') || path.chars().any(char::is_control) {
+    return None;
+  }
+
+  Some(path)
+}
+
+/// Where a form posted without javascript goes once done: its `return_to` field, else the page it
+/// was posted from, else the front page. `anchor` then scrolls to what was acted on.
+#[cfg(feature = "ssr")]
+pub async fn return_path(return_to: Option<String>, anchor: Option<String>) -> String {
+  use actix_web::{http::header, HttpRequest};
+  use leptos_actix::extract;
+
+  let path = match extract::<HttpRequest>().await {
+    Ok(req) => {
+      let host = req.connection_info().host().to_string();
+      let referer = req
+        .headers()
+        .get(header::REFERER)
+        .and_then(|r| r.to_str().ok())
+        .map(String::from);
+
+      return_to
+        .and_then(|r| local_path(&r, &host))
+        .or_else(|| referer.and_then(|r| local_path(&r, &host)))
+    }
+    Err(_) => None,
+  };
+
+  let path = path.unwrap_or_else(|| "/".to_string());
+
+  match anchor {
+    Some(anchor) => format!("{path}#{anchor}"),
+    None => path,
+  }
+}
+
+/// The current page, for the `return_to` field of forms that have to work without javascript.
+pub fn use_return_to() -> impl Fn() -> String + Copy + 'static {
+  let location = leptos_router::use_location();
+  let (pathname, search) = (location.pathname, location.search);
+
+  move || {
+    let search = search.get();
+    let search = search.trim_start_matches('?');
+    if search.is_empty() {
+      pathname.get()
+    } else {
+      format!("{}?{}", pathname.get(), search)
+    }
+  }
+}
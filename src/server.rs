@@ -0,0 +1,75 @@
+use crate::{
+  config::AppConfig,
+  flash::FlashKey,
+  proxy::{self, ProxyClient},
+  App,
+};
+use actix_files::Files;
+use actix_web::web;
+use awc::Client;
+use leptos::*;
+use leptos_actix::{handle_server_fns_with_context, LeptosRoutes};
+use leptos_router::RouteListing;
+
+#[actix_web::get("favicon.svg")]
+async fn favicon(
+  leptos_options: web::Data<leptos::LeptosOptions>,
+) -> actix_web::Result<actix_files::NamedFile> {
+  let leptos_options = leptos_options.into_inner();
+  let site_root = &leptos_options.site_root;
+  Ok(actix_files::NamedFile::open(format!(
+    "{site_root}/favicon.svg"
+  ))?)
+}
+
+#[actix_web::get("icons.svg")]
+async fn icons(
+  leptos_options: web::Data<leptos::LeptosOptions>,
+) -> actix_web::Result<actix_files::NamedFile> {
+  let leptos_options = leptos_options.into_inner();
+  let site_root = &leptos_options.site_root;
+  Ok(actix_files::NamedFile::open(format!(
+    "{site_root}/icons.svg"
+  ))?)
+}
+
+/// Mounts everything the actix binary serves. Called once per worker, and by the integration tests
+/// against a mock backend.
+pub fn configure(
+  leptos_options: LeptosOptions,
+  routes: Vec<RouteListing>,
+  config: AppConfig,
+) -> impl FnOnce(&mut web::ServiceConfig) {
+  move |cfg| {
+    let site_root = leptos_options.site_root.clone();
+    let client_options = config.client_options();
+    let flash_key = FlashKey::new(&config.flash);
+    let provide_config = {
+      let client_options = client_options.clone();
+      let config = config.clone();
+      move || {
+        provide_context(client_options.clone());
+        provide_context(config.clone());
+        provide_context(flash_key.clone());
+      }
+    };
+
+    cfg
+      .route("/api/{tail:.*}", web::to(proxy::forward))
+      .route("/pictrs/{tail:.*}", web::to(proxy::forward))
+      .route(
+        "/serverfn/{tail:.*}",
+        handle_server_fns_with_context(provide_config.clone()),
+      )
+      .service(Files::new("/pkg", format!("{site_root}/pkg")))
+      .service(Files::new("/assets", site_root))
+      .service(favicon)
+      .service(icons)
+      .leptos_routes_with_context(leptos_options.to_owned(), routes, provide_config, App)
+      .app_data(web::Data::new(leptos_options))
+      .app_data(web::Data::new(Client::new()))
+      .app_data(web::Data::new(client_options))
+      .app_data(web::Data::new(ProxyClient::new(&config.proxy)))
+      .app_data(web::Data::new(config.proxy.clone()));
+  }
+}
@@ -1,2 +1,3 @@
 pub mod comment_node;
 pub mod comment_nodes;
+pub mod reply_form;
@@ -0,0 +1,132 @@
+use crate::{
+  errors::{LemmyAppError, LemmyAppErrorType},
+  flash::use_flash,
+  i18n::*,
+  lemmy_client::*,
+  notifications::use_notifications,
+  redirect::use_return_to,
+};
+#[cfg(feature = "ssr")]
+use crate::{flash::finish_form, redirect::return_path};
+use lemmy_api_common::{
+  comment::{CommentResponse, CreateComment},
+  lemmy_db_schema::newtypes::{CommentId, PostId},
+};
+use leptos::*;
+use leptos_router::*;
+use web_sys::SubmitEvent;
+
+async fn try_create_comment(
+  client: LemmyClient,
+  form: CreateComment,
+) -> Result<CommentResponse, LemmyAppError> {
+  if form.content.trim().is_empty() {
+    // tells the form which of the reply boxes on the page was left empty
+    return Err(LemmyAppError {
+      error_type: LemmyAppErrorType::EmptyContent,
+      content: form
+        .parent_id
+        .map(|p| format!("{}", p.0))
+        .unwrap_or_default(),
+    });
+  }
+
+  client.create_comment(form).await
+}
+
+#[server(CreateCommentFn, "/serverfn")]
+pub async fn create_comment_fn(
+  post_id: i32,
+  parent_id: Option<i32>,
+  content: String,
+  return_to: Option<String>,
+) -> Result<CommentResponse, ServerFnError<LemmyAppError>> {
+  let form = CreateComment {
+    content,
+    post_id: PostId(post_id),
+    parent_id: parent_id.map(CommentId),
+    ..Default::default()
+  };
+  let result = try_create_comment(use_lemmy_client(), form).await;
+
+  let back = return_path(return_to, parent_id.map(|id| format!("comment-{id}"))).await;
+
+  finish_form(result, &back, None).await
+}
+
+/// Answers a comment, or the post itself without a `parent_id`.
+#[component]
+pub fn ReplyForm(post_id: PostId, parent_id: Option<CommentId>) -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let client = store_value(use_lemmy_client());
+  let return_to = use_return_to();
+
+  let create_comment_action = create_server_action::<CreateCommentFn>();
+  let content = RwSignal::new(String::new());
+  let validation = create_rw_signal::<String>("".into());
+
+  let parent = parent_id.map(|p| format!("{}", p.0)).unwrap_or_default();
+
+  // this reply was left empty without javascript
+  if let Some(LemmyAppError {
+    error_type: LemmyAppErrorType::EmptyContent,
+    content: failed,
+  }) = use_flash().error()
+  {
+    if failed == parent {
+      validation.set("textarea-error".to_string());
+    }
+  }
+
+  let on_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let form = CreateComment {
+          content: content.get_untracked(),
+          post_id,
+          parent_id,
+          ..Default::default()
+        };
+
+        let result = try_create_comment(client.get_value(), form).await;
+
+        match result {
+          Ok(_o) => {
+            content.set(String::new());
+            validation.set("".to_string());
+            notifications.success(t!(i18n, reply_sent)().to_string());
+          }
+          Err(e) => {
+            if e.error_type == LemmyAppErrorType::EmptyContent {
+              validation.set("textarea-error".to_string());
+            }
+            notifications.error(e);
+          }
+        }
+      },
+    );
+  };
+
+  view! {
+    <ActionForm action=create_comment_action on:submit=on_submit class="flex flex-col gap-2">
+      <input type="hidden" name="post_id" value=format!("{}", post_id.0)/>
+      {parent_id
+          .map(|p| view! { <input type="hidden" name="parent_id" value=format!("{}", p.0)/> })}
+      <input type="hidden" name="return_to" value=return_to/>
+      <textarea
+        class=move || format!("textarea textarea-bordered {}", validation.get())
+        name="content"
+        placeholder=move || t!(i18n, reply)().to_string()
+        prop:value=content
+        on:input=move |e| content.set(event_target_value(&e))
+      ></textarea>
+      <button type="submit" class="btn btn-sm self-start">
+        {t!(i18n, reply)}
+      </button>
+    </ActionForm>
+  }
+}
@@ -0,0 +1,63 @@
+use crate::{
+  errors::{message_from_error, LemmyAppError, LemmyAppErrorType},
+  i18n::*,
+};
+use leptos::*;
+use leptos_meta::Title;
+
+/// Rendered in place of a page that failed to load, with a status code to match during SSR.
+#[component]
+pub fn ErrorPage(error: LemmyAppError) -> impl IntoView {
+  let i18n = use_i18n();
+  let status = error.error_type.status_code();
+
+  #[cfg(feature = "ssr")]
+  {
+    use actix_web::http::StatusCode;
+
+    let resp = expect_context::<leptos_actix::ResponseOptions>();
+    resp.set_status(StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR));
+  }
+
+  let heading = move || {
+    match status {
+      404 => t!(i18n, error_page_not_found)(),
+      403 => t!(i18n, error_page_forbidden)(),
+      503 => t!(i18n, error_page_unavailable)(),
+      _ => t!(i18n, error_page_internal)(),
+    }
+    .to_string()
+  };
+  let message = message_from_error(&error);
+
+  view! {
+    <Title text=heading/>
+    <main class="mx-auto hero">
+      <div class="hero-content text-center">
+        <div class="max-w-md">
+          <p class="text-6xl font-bold">{status}</p>
+          <h1 class="py-4 text-2xl">{heading}</h1>
+          <p class="pb-6">{message}</p>
+          <a class="btn btn-primary" href="/">
+            {t!(i18n, back_to_home)}
+          </a>
+        </div>
+      </div>
+    </main>
+  }
+}
+
+/// Fallback of the `ErrorBoundary` around the routes, showing the first error raised by the page.
+/// Anything that is not a `LemmyAppError` counts as an internal error.
+pub fn routed_error_page(errors: RwSignal<Errors>) -> impl IntoView {
+  move || {
+    let error = errors
+      .get()
+      .into_iter()
+      .next()
+      .and_then(|(_, e)| e.downcast_ref::<LemmyAppError>().cloned())
+      .unwrap_or_else(|| LemmyAppErrorType::InternalServerError.into());
+
+    view! { <ErrorPage error/> }
+  }
+}
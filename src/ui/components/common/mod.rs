@@ -1,3 +1,7 @@
+pub mod error_page;
 pub mod icon;
 pub mod nav;
+pub mod qr_code;
+pub mod sort;
 pub mod text_input;
+pub mod toasts;
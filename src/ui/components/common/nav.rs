@@ -1,41 +1,36 @@
 use crate::{
-  cookie::{remove_cookie, set_cookie},
-  errors::{message_from_error, LemmyAppError},
+  config::use_public_config,
+  cookie::set_cookie,
+  errors::LemmyAppError,
+  flash::use_flash,
   i18n::*,
   lemmy_client::*,
+  notifications::use_notifications,
   ui::components::common::icon::{
     Icon,
     IconType::{Donate, Notifications, Search},
   },
 };
+#[cfg(feature = "ssr")]
+use crate::{
+  cookie::{remove_cookie, AUTH_COOKIE},
+  errors::LemmyAppResult,
+  flash::{finish_form, server_fn_error},
+};
 use lemmy_api_common::site::GetSiteResponse;
 use leptos::*;
 use leptos_router::*;
 use web_sys::SubmitEvent;
 
 #[server(LogoutFn, "/serverfn")]
-pub async fn logout() -> Result<(), ServerFnError> {
-  use leptos_actix::redirect;
-  let result = LemmyClient.logout().await;
-  match result {
-    Ok(_o) => {
-      let r = remove_cookie("jwt").await;
-      match r {
-        Ok(_o) => {
-          redirect("/");
-          Ok(())
-        }
-        Err(e) => {
-          redirect(&format!("/login?error={}", serde_json::to_string(&e)?)[..]);
-          Ok(())
-        }
-      }
-    }
-    Err(e) => {
-      redirect(&format!("/login?error={}", serde_json::to_string(&e)?)[..]);
-      Ok(())
-    }
+pub async fn logout() -> Result<(), ServerFnError<LemmyAppError>> {
+  let result: LemmyAppResult<()> = async {
+    use_lemmy_client().logout().await?;
+    remove_cookie(AUTH_COOKIE).await
   }
+  .await;
+
+  finish_form(result, "/login", Some("/")).await
 }
 
 #[server(ChangeLangFn, "/serverfn")]
@@ -43,58 +38,98 @@ pub async fn change_lang(lang: String) -> Result<(), ServerFnError> {
   let _ = set_cookie(
     "i18n_pref_locale",
     &lang.to_lowercase(),
-    &core::time::Duration::from_secs(604800),
+    &use_public_config().cookie.max_age(),
   )
   .await;
   Ok(())
 }
 
 #[server(ChangeThemeFn, "/serverfn")]
-pub async fn change_theme(theme: String) -> Result<(), ServerFnError> {
-  use leptos_actix::redirect;
-  let r = set_cookie("theme", &theme, &core::time::Duration::from_secs(604800)).await;
+pub async fn change_theme(theme: String) -> Result<(), ServerFnError<LemmyAppError>> {
+  let r = set_cookie("theme", &theme, &use_public_config().cookie.max_age()).await;
   match r {
     Ok(_o) => Ok(()),
-    Err(e) => {
-      redirect(&format!("/login?error={}", serde_json::to_string(&e)?)[..]);
-      Ok(())
-    }
+    Err(e) => Err(server_fn_error("/login", e).await),
   }
 }
 
+/// Has the unread count of the top bar checked again, for pages that mark things read without
+/// navigating. Provided by `App`.
+#[derive(Clone, Copy)]
+pub struct UnreadCount(Trigger);
+
+impl UnreadCount {
+  pub fn refetch(&self) {
+    self.0.notify();
+  }
+}
+
+pub fn provide_unread_count() -> UnreadCount {
+  let unread_count = UnreadCount(create_trigger());
+  provide_context(unread_count);
+  unread_count
+}
+
+pub fn use_unread_count() -> UnreadCount {
+  expect_context::<UnreadCount>()
+}
+
 #[component]
 pub fn TopNav(
   site_signal: RwSignal<Option<Result<GetSiteResponse, LemmyAppError>>>,
 ) -> impl IntoView {
   let i18n = use_i18n();
 
-  let error = expect_context::<RwSignal<Option<LemmyAppError>>>();
+  let notifications = use_notifications();
 
-  if let Some(Err(e)) = site_signal.get() {
-    error.set(Some(e));
+  let flash = use_flash();
+  if let Some(e) = flash.error() {
+    notifications.error(e);
   }
 
-  let query = use_query_map();
-  let ssr_error = move || query.with(|params| params.get("error").cloned());
-
-  if let Some(e) = ssr_error() {
-    if !e.is_empty() {
-      let r = serde_json::from_str::<LemmyAppError>(&e[..]);
-
-      match r {
-        Ok(e) => {
-          error.set(Some(e));
-        }
-        Err(_) => {
-          logging::error!("error decoding error - log and ignore in UI?");
-        }
-      }
+  // a flash belongs to the page the redirect led to
+  let pathname = use_location().pathname;
+  create_effect(move |prev: Option<String>| {
+    let path = pathname.get();
+    if prev.is_some() {
+      flash.clear();
     }
-  }
+    path
+  });
 
   let user = expect_context::<RwSignal<Option<bool>>>();
+  let client = store_value(use_lemmy_client());
+
+  let logged_in = move || {
+    matches!(
+      site_signal.get(),
+      Some(Ok(GetSiteResponse {
+        my_user: Some(_),
+        ..
+      }))
+    )
+  };
+
+  // checked again on every navigation, and whenever the inbox marks things read
+  let refetch_unread = use_unread_count();
+  let unread_count = create_resource(
+    move || {
+      refetch_unread.0.track();
+      (logged_in(), pathname.get())
+    },
+    move |(logged_in, _path)| async move {
+      if !logged_in {
+        return None;
+      }
+      let result = client.get_value().get_unread_count().await;
+      result
+        .ok()
+        .map(|c| c.replies + c.mentions + c.private_messages)
+    },
+  );
 
   let logout_action = create_server_action::<LogoutFn>();
+  let navigate = store_value(use_navigate());
 
   let on_logout_submit = move |ev: SubmitEvent| {
     ev.prevent_default();
@@ -102,15 +137,16 @@ pub fn TopNav(
     create_local_resource(
       move || (),
       move |()| async move {
-        let result = LemmyClient.logout().await;
+        // the session cookie is HttpOnly, so only the server can clear it
+        let result = logout().await;
         match result {
           Ok(_o) => {
-            let _ = remove_cookie("jwt").await;
             user.set(Some(false));
+            navigate.with_value(|navigate| navigate("/", Default::default()));
           }
           Err(e) => {
             logging::warn!("logout error {:#?}", e);
-            error.set(Some(e));
+            notifications.error(e.into());
           }
         }
       },
@@ -126,7 +162,7 @@ pub fn TopNav(
       let _res = create_local_resource(
         move || theme_name.to_string(),
         move |t| async move {
-          let _ = set_cookie("theme", &t, &core::time::Duration::from_secs(604800)).await;
+          let _ = set_cookie("theme", &t, &use_public_config().cookie.max_age()).await;
         },
       );
       ui_theme.set(Some(theme_name.to_string()));
@@ -236,14 +272,7 @@ pub fn TopNav(
             </details>
           </li>
           <Show
-            when=move || {
-                if let Some(Ok(GetSiteResponse { my_user: Some(_), .. })) = site_signal.get() {
-                    true
-                } else {
-                    false
-                }
-            }
-
+            when=logged_in
             fallback=move || {
                 view! {
                   <li>
@@ -261,6 +290,16 @@ pub fn TopNav(
                 <span title=t!(i18n, unread_messages)>
                   <Icon icon=Notifications/>
                 </span>
+                <Transition fallback=|| {}>
+                  {move || {
+                      unread_count
+                          .get()
+                          .flatten()
+                          .filter(|c| *c > 0)
+                          .map(|c| view! { <span class="badge badge-sm badge-primary">{c}</span> })
+                  }}
+
+                </Transition>
               </A>
             </li>
             <li>
@@ -310,26 +349,6 @@ pub fn TopNav(
         </ul>
       </div>
     </nav>
-    <Show
-      when=move || error.get().is_some()
-      fallback=move || {
-          view! { <div class="hidden"></div> }
-      }
-    >
-
-      {move || {
-          error
-              .get()
-              .map(|err| {
-                  view! {
-                    <div class="container mx-auto alert alert-error">
-                      <span>{message_from_error(&err)} " - " {err.content}</span>
-                    </div>
-                  }
-              })
-      }}
-
-    </Show>
   }
 }
 
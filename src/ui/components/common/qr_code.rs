@@ -0,0 +1,60 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+// the light margin scanners need around a code, in modules
+const QUIET_ZONE: usize = 4;
+
+/// A QR code as the dark modules of an SVG path, one unit square each, offset by the quiet zone,
+/// along with its width in modules. Encoded on the server, which keeps the encoder out of the
+/// browser.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QrSvg {
+  width: usize,
+  path: String,
+}
+
+impl QrSvg {
+  /// The QR code of `data`, `None` where `data` is too long to fit.
+  #[cfg(feature = "ssr")]
+  pub fn new(data: &str) -> Option<Self> {
+    use qrcode::{Color, QrCode as Code};
+
+    let code = Code::new(data.as_bytes()).ok()?;
+    let width = code.width();
+
+    let path = code
+      .to_colors()
+      .into_iter()
+      .enumerate()
+      .filter(|(_, color)| *color == Color::Dark)
+      .map(|(i, _)| {
+        format!(
+          "M{} {}h1v1h-1z",
+          i % width + QUIET_ZONE,
+          i / width + QUIET_ZONE
+        )
+      })
+      .collect::<String>();
+
+    Some(Self { width, path })
+  }
+}
+
+/// A QR code, drawn here rather than by a service that would get to see what it holds. Dark on
+/// light whatever the theme, as scanners expect.
+#[component]
+pub fn QrCode(code: QrSvg, #[prop(optional)] class: MaybeProp<TextProp>) -> impl IntoView {
+  let size = code.width + 2 * QUIET_ZONE;
+
+  view! {
+    <svg
+      class=class
+      viewBox=format!("0 0 {size} {size}")
+      shape-rendering="crispEdges"
+      role="img"
+    >
+      <rect width="100%" height="100%" fill="#fff"></rect>
+      <path d=code.path fill="#000"></path>
+    </svg>
+  }
+}
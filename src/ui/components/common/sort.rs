@@ -0,0 +1,53 @@
+use crate::i18n::*;
+use lemmy_api_common::lemmy_db_schema::SortType;
+use leptos::*;
+
+/// every sort the api knows, in the order they are offered
+pub const SORT_TYPES: [SortType; 19] = [
+  SortType::Active,
+  SortType::Hot,
+  SortType::New,
+  SortType::Old,
+  SortType::TopHour,
+  SortType::TopSixHour,
+  SortType::TopTwelveHour,
+  SortType::TopDay,
+  SortType::TopWeek,
+  SortType::TopMonth,
+  SortType::TopThreeMonths,
+  SortType::TopSixMonths,
+  SortType::TopNineMonths,
+  SortType::TopYear,
+  SortType::TopAll,
+  SortType::MostComments,
+  SortType::NewComments,
+  SortType::Controversial,
+  SortType::Scaled,
+];
+
+pub fn sort_type_label(sort: SortType) -> String {
+  let i18n = use_i18n();
+
+  match sort {
+    SortType::Active => t!(i18n, active)(),
+    SortType::Hot => t!(i18n, hot)(),
+    SortType::New => t!(i18n, new)(),
+    SortType::Old => t!(i18n, old)(),
+    SortType::TopHour => t!(i18n, top_hour)(),
+    SortType::TopSixHour => t!(i18n, top_six_hour)(),
+    SortType::TopTwelveHour => t!(i18n, top_twelve_hour)(),
+    SortType::TopDay => t!(i18n, top_day)(),
+    SortType::TopWeek => t!(i18n, top_week)(),
+    SortType::TopMonth => t!(i18n, top_month)(),
+    SortType::TopThreeMonths => t!(i18n, top_three_months)(),
+    SortType::TopSixMonths => t!(i18n, top_six_months)(),
+    SortType::TopNineMonths => t!(i18n, top_nine_months)(),
+    SortType::TopYear => t!(i18n, top_year)(),
+    SortType::TopAll => t!(i18n, top_all)(),
+    SortType::MostComments => t!(i18n, most_comments)(),
+    SortType::NewComments => t!(i18n, new_comments)(),
+    SortType::Controversial => t!(i18n, controversial)(),
+    SortType::Scaled => t!(i18n, scaled)(),
+  }
+  .to_string()
+}
@@ -0,0 +1,56 @@
+use crate::{
+  errors::message_from_error,
+  i18n::*,
+  notifications::{use_notifications, Notification, NotificationMessage, Severity},
+};
+use leptos::*;
+
+#[component]
+pub fn Toasts() -> impl IntoView {
+  let notifications = use_notifications();
+
+  view! {
+    <div class="toast toast-top toast-center z-[2]" role="status" aria-live="polite">
+      <For
+        each=move || notifications.list().get()
+        key=|n| (n.id, n.count)
+        children=move |n| view! { <Toast notification=n/> }
+      />
+    </div>
+  }
+}
+
+#[component]
+fn Toast(notification: Notification) -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+
+  let class = match notification.severity {
+    Severity::Info => "alert alert-info",
+    Severity::Success => "alert alert-success",
+    Severity::Error => "alert alert-error",
+  };
+
+  let message = match &notification.message {
+    NotificationMessage::Error(e) => message_from_error(e),
+    NotificationMessage::Text(t) => t.clone(),
+  };
+
+  let id = notification.id;
+
+  view! {
+    <div class=class>
+      <span>
+        {message}
+        {(notification.count > 1).then(|| format!(" ({})", notification.count))}
+      </span>
+      <button
+        class="btn btn-sm btn-ghost"
+        title=move || t!(i18n, dismiss)().to_string()
+        on:click=move |_| notifications.dismiss(id)
+      >
+        "✕"
+      </button>
+    </div>
+  }
+}
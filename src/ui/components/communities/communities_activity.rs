@@ -1,10 +1,305 @@
+use crate::{
+  i18n::*,
+  lemmy_client::*,
+  notifications::use_notifications,
+  ui::components::{
+    common::sort::{sort_type_label, SORT_TYPES},
+    community::subscribe_button::SubscribeButton,
+  },
+};
+use lemmy_api_common::{
+  community::ListCommunities,
+  lemmy_db_schema::{ListingType, SearchType, SortType},
+  lemmy_db_views_actor::structs::CommunityView,
+  site::Search,
+};
 use leptos::*;
+use leptos_router::*;
 
+const PAGE_LIMIT: i64 = 20;
+
+/// Every community the instance knows of, a page at a time. The listing type, sort, NSFW toggle,
+/// search and page all live in the query string so that the directory works without javascript.
 #[component]
 pub fn CommunitiesActivity() -> impl IntoView {
+  let i18n = use_i18n();
+
+  let notifications = use_notifications();
+  let user = expect_context::<RwSignal<Option<bool>>>();
+  let client = store_value(use_lemmy_client());
+
+  let query = use_query_map();
+
+  let list_func = move || {
+    query
+      .get()
+      .get("list")
+      .and_then(|l| serde_json::from_str::<ListingType>(l).ok())
+      .unwrap_or(ListingType::All)
+  };
+
+  let sort_func = move || {
+    query
+      .get()
+      .get("sort")
+      .and_then(|s| serde_json::from_str::<SortType>(s).ok())
+      .unwrap_or(SortType::TopMonth)
+  };
+
+  let nsfw_func = move || query.get().get("nsfw").is_some_and(|n| n == "true");
+
+  let search_func = move || {
+    query
+      .get()
+      .get("q")
+      .map(|q| q.trim().to_string())
+      .unwrap_or_default()
+  };
+
+  let page_func = move || {
+    query
+      .get()
+      .get("page")
+      .and_then(|p| p.parse::<i64>().ok())
+      .filter(|p| *p > 0)
+      .unwrap_or(1)
+  };
+
+  let communities = create_resource(
+    move || {
+      (
+        user.get(),
+        list_func(),
+        sort_func(),
+        nsfw_func(),
+        search_func(),
+        page_func(),
+      )
+    },
+    move |(_user, list_type, sort_type, show_nsfw, search, page)| async move {
+      // the community list has no name filter, so a search goes through the search api instead
+      let result = if search.is_empty() {
+        let form = ListCommunities {
+          type_: Some(list_type),
+          sort: Some(sort_type),
+          show_nsfw: Some(show_nsfw),
+          page: Some(page),
+          limit: Some(PAGE_LIMIT),
+        };
+        client.get_value().list_communities(form).await.map(|r| {
+          let more = r.communities.len() as i64 >= PAGE_LIMIT;
+          (r.communities, more)
+        })
+      } else {
+        let form = Search {
+          q: search,
+          type_: Some(SearchType::Communities),
+          sort: Some(sort_type),
+          listing_type: Some(list_type),
+          page: Some(page),
+          limit: Some(PAGE_LIMIT),
+          ..Default::default()
+        };
+        // search follows the account's NSFW setting, so apply the toggle here as well, after
+        // telling from the whole page whether there is another one
+        client.get_value().search(form).await.map(|r| {
+          let more = r.communities.len() as i64 >= PAGE_LIMIT;
+          let communities = r
+            .communities
+            .into_iter()
+            .filter(|c| show_nsfw || !c.community.nsfw)
+            .collect::<Vec<_>>();
+          (communities, more)
+        })
+      };
+
+      match result {
+        Ok(o) => Some(o),
+        Err(e) => {
+          notifications.error(e);
+          None
+        }
+      }
+    },
+  );
+
+  let list_href = move |list_type: ListingType| {
+    let mut query_params = query.get();
+    query_params.insert(
+      "list".into(),
+      serde_json::to_string(&list_type).unwrap_or_default(),
+    );
+    query_params.remove("page");
+    query_params.to_query_string()
+  };
+
+  let list_class = move |list_type: ListingType| {
+    format!(
+      "btn join-item {}",
+      if list_type == list_func() {
+        "btn-active"
+      } else {
+        ""
+      }
+    )
+  };
+
+  let page_href = move |page: i64| {
+    let mut query_params = query.get();
+    query_params.insert("page".into(), page.to_string());
+    query_params.to_query_string()
+  };
+
   view! {
     <main class="mx-auto">
-      <h2 class="p-6 text-4xl">"Communities page"</h2>
+      <h2 class="p-6 text-4xl">{t!(i18n, communities)}</h2>
+      <div class="flex flex-wrap items-center gap-3 px-6 mb-3">
+        <div class="join">
+          <A
+            href=move || list_href(ListingType::All)
+            class=move || list_class(ListingType::All)
+          >
+            {t!(i18n, all)}
+          </A>
+          <A
+            href=move || list_href(ListingType::Local)
+            class=move || list_class(ListingType::Local)
+          >
+            {t!(i18n, local)}
+          </A>
+          <A
+            href=move || list_href(ListingType::Subscribed)
+            class=move || list_class(ListingType::Subscribed)
+          >
+            {t!(i18n, subscribed)}
+          </A>
+        </div>
+        <Form method="GET" action="/communities" class="flex flex-wrap items-center gap-3">
+          <input
+            type="hidden"
+            name="list"
+            value=move || serde_json::to_string(&list_func()).unwrap_or_default()
+          />
+          <select class="select select-bordered" name="sort">
+            {SORT_TYPES
+                .into_iter()
+                .map(|s| {
+                    view! {
+                      <option
+                        value=serde_json::to_string(&s).unwrap_or_default()
+                        selected=move || s == sort_func()
+                      >
+                        {move || sort_type_label(s)}
+                      </option>
+                    }
+                })
+                .collect_view()}
+          </select>
+          <label class="label cursor-pointer gap-2">
+            <input
+              type="checkbox"
+              class="checkbox"
+              name="nsfw"
+              value="true"
+              checked=nsfw_func
+            />
+            <span class="label-text">{t!(i18n, show_nsfw)}</span>
+          </label>
+          <input
+            type="search"
+            class="input input-bordered"
+            name="q"
+            placeholder=move || t!(i18n, search_communities)().to_string()
+            value=search_func
+          />
+          <button type="submit" class="btn btn-primary">
+            {t!(i18n, search)}
+          </button>
+        </Form>
+      </div>
+      <Transition fallback=|| {}>
+        {move || {
+            communities
+                .get()
+                .unwrap_or(None)
+                .map(|(c, more)| {
+                    let page = page_func();
+                    view! {
+                      <table class="table">
+                        <thead>
+                          <tr>
+                            <th>{t!(i18n, community)}</th>
+                            <th class="text-right">{t!(i18n, subscribers)}</th>
+                            <th class="text-right">{t!(i18n, posts)}</th>
+                            <th class="text-right">{t!(i18n, users_per_month)}</th>
+                            <th></th>
+                          </tr>
+                        </thead>
+                        <tbody>
+                          <For
+                            each=move || c.clone()
+                            key=|cv| cv.community.id
+                            children=move |cv: CommunityView| {
+                                view! { <CommunityRow community_view=cv/> }
+                            }
+                          />
+
+                        </tbody>
+                      </table>
+                      <div class="px-6">
+                        {(page > 1)
+                            .then(|| {
+                                view! {
+                                  <A href=page_href(page - 1) class="btn">
+                                    "Prev"
+                                  </A>
+                                }
+                            })}
+                        " "
+                        {more
+                            .then(|| {
+                                view! {
+                                  <A href=page_href(page + 1) class="btn">
+                                    "Next"
+                                  </A>
+                                }
+                            })}
+
+                      </div>
+                    }
+                })
+        }}
+
+      </Transition>
     </main>
   }
 }
+
+#[component]
+fn CommunityRow(community_view: CommunityView) -> impl IntoView {
+  let community_view = create_rw_signal(community_view);
+
+  view! {
+    <tr>
+      <td>
+        <A
+          class="font-bold link link-accent"
+          href=move || format!("/c/{}", community_view.get().community.name)
+        >
+          {move || community_view.get().community.title}
+        </A>
+        " "
+        {move || {
+            community_view.get().community.nsfw.then(|| view! { <span class="badge">"NSFW"</span> })
+        }}
+
+      </td>
+      <td class="text-right">{move || community_view.get().counts.subscribers}</td>
+      <td class="text-right">{move || community_view.get().counts.posts}</td>
+      <td class="text-right">{move || community_view.get().counts.users_active_month}</td>
+      <td class="text-right">
+        <SubscribeButton community_view/>
+      </td>
+    </tr>
+  }
+}
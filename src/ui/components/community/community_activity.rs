@@ -0,0 +1,163 @@
+use crate::{
+  i18n::*,
+  lemmy_client::*,
+  ui::components::{community::subscribe_button::SubscribeButton, post::post_feed::PostFeed},
+};
+use lemmy_api_common::{
+  community::{GetCommunity, GetCommunityResponse},
+  lemmy_db_views_actor::structs::CommunityView,
+};
+use leptos::*;
+use leptos_router::*;
+
+#[component]
+pub fn CommunityActivity() -> impl IntoView {
+  let params = use_params_map();
+  let user = expect_context::<RwSignal<Option<bool>>>();
+  let client = store_value(use_lemmy_client());
+
+  let name = move || params.get().get("name").cloned().unwrap_or_default();
+
+  let community = create_resource(
+    move || (user.get(), name()),
+    move |(_user, name)| async move {
+      let form = GetCommunity {
+        id: None,
+        name: Some(name),
+      };
+      client.get_value().get_community(form).await
+    },
+  );
+
+  view! {
+    <Transition fallback=|| {}>
+      {move || {
+          // a community that fails to load fails the whole page, see `routed_error_page`
+          community
+              .get()
+              .map(|res| {
+                  res.map(|res| view! { <CommunityHeader community_view=res.community_view/> })
+              })
+      }}
+
+    </Transition>
+    <PostFeed community_name=Signal::derive(move || Some(name()))>
+      <Transition fallback=|| {}>
+        {move || {
+            community
+                .get()
+                .and_then(Result::ok)
+                .map(|res| view! { <CommunitySidebar community=res/> })
+        }}
+
+      </Transition>
+    </PostFeed>
+  }
+}
+
+#[component]
+fn CommunityHeader(community_view: CommunityView) -> impl IntoView {
+  let i18n = use_i18n();
+
+  let community_view = create_rw_signal(community_view);
+
+  view! {
+    <div class="card w-full bg-base-300 text-base-content mb-3">
+      {move || {
+          community_view
+              .get()
+              .community
+              .banner
+              .map(|b| {
+                  view! {
+                    <figure>
+                      <img class="w-full max-h-48 object-cover" src=b.inner().to_string()/>
+                    </figure>
+                  }
+              })
+      }}
+      <div class="card-body flex-row flex-wrap items-center gap-4">
+        {move || {
+            community_view
+                .get()
+                .community
+                .icon
+                .map(|i| {
+                    view! {
+                      <img class="w-16 h-16 rounded-full" src=i.inner().to_string()/>
+                    }
+                })
+        }}
+        <div class="flex-grow">
+          <h1 class="card-title text-2xl">{move || community_view.get().community.title}</h1>
+          <span class="text-sm">"!" {move || community_view.get().community.name}</span>
+          <p>
+            <span class="badge badge-neutral inline-block whitespace-nowrap">
+              {move || community_view.get().counts.subscribers}
+              " "
+              {t!(i18n, subscribers)}
+            </span>
+            " "
+            <span class="badge badge-neutral inline-block whitespace-nowrap">
+              {move || community_view.get().counts.users_active_month}
+              " "
+              {t!(i18n, users_per_month)}
+            </span>
+            " "
+            <span class="badge badge-neutral inline-block whitespace-nowrap">
+              {move || community_view.get().counts.posts}
+              " "
+              {t!(i18n, posts)}
+            </span>
+            " "
+            <span class="badge badge-neutral inline-block whitespace-nowrap">
+              {move || community_view.get().counts.comments}
+              " "
+              {t!(i18n, comments)}
+            </span>
+          </p>
+        </div>
+        <SubscribeButton community_view/>
+      </div>
+    </div>
+  }
+}
+
+#[component]
+fn CommunitySidebar(community: GetCommunityResponse) -> impl IntoView {
+  let i18n = use_i18n();
+
+  let GetCommunityResponse {
+    community_view,
+    moderators,
+    ..
+  } = community;
+
+  view! {
+    <div class="card w-full bg-base-300 text-base-content mb-3">
+      <div class="card-body">
+        <h2 class="card-title">{t!(i18n, about_community)}</h2>
+        <p class="whitespace-pre-wrap">{community_view.community.description}</p>
+        <h3 class="card-title">{t!(i18n, moderators)}</h3>
+        <p>
+          <For
+            each=move || moderators.clone()
+            key=|m| m.moderator.id
+            children=move |m| {
+                view! {
+                  <A
+                    href=format!("/u/{}", m.moderator.name)
+                    class="badge badge-neutral inline-block whitespace-nowrap"
+                  >
+                    {m.moderator.display_name.unwrap_or(m.moderator.name)}
+                  </A>
+                  " "
+                }
+            }
+          />
+
+        </p>
+      </div>
+    </div>
+  }
+}
@@ -0,0 +1,2 @@
+pub mod community_activity;
+pub mod subscribe_button;
@@ -0,0 +1,95 @@
+use crate::{
+  errors::LemmyAppError,
+  i18n::*,
+  lemmy_client::*,
+  notifications::use_notifications,
+  redirect::use_return_to,
+};
+#[cfg(feature = "ssr")]
+use crate::{flash::finish_form, redirect::return_path};
+use lemmy_api_common::{
+  community::{CommunityResponse, FollowCommunity},
+  lemmy_db_schema::SubscribedType,
+  lemmy_db_views_actor::structs::CommunityView,
+};
+use leptos::*;
+use leptos_router::*;
+use web_sys::SubmitEvent;
+
+#[server(FollowCommunityFn, "/serverfn")]
+pub async fn follow_community_fn(
+  community_id: i32,
+  follow: bool,
+  return_to: Option<String>,
+) -> Result<CommunityResponse, ServerFnError<LemmyAppError>> {
+  use lemmy_api_common::lemmy_db_schema::newtypes::CommunityId;
+
+  let form = FollowCommunity {
+    community_id: CommunityId(community_id),
+    follow,
+  };
+  let result = use_lemmy_client().follow_community(form).await;
+
+  let back = return_path(return_to, None).await;
+
+  finish_form(result, &back, None).await
+}
+
+/// Subscribes to or unsubscribes from a community, updating `community_view` with the result.
+#[component]
+pub fn SubscribeButton(community_view: RwSignal<CommunityView>) -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let client = store_value(use_lemmy_client());
+  let return_to = use_return_to();
+
+  let follow_action = create_server_action::<FollowCommunityFn>();
+
+  // a pending subscription to a remote community can be cancelled too
+  let follow = move || community_view.get().subscribed == SubscribedType::NotSubscribed;
+
+  let on_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let form = FollowCommunity {
+          community_id: community_view.get().community.id,
+          follow: follow(),
+        };
+
+        let result = client.get_value().follow_community(form).await;
+
+        match result {
+          Ok(o) => {
+            community_view.set(o.community_view);
+          }
+          Err(e) => {
+            notifications.error(e);
+          }
+        }
+      },
+    );
+  };
+
+  view! {
+    <ActionForm action=follow_action on:submit=on_submit>
+      <input
+        type="hidden"
+        name="community_id"
+        value=move || format!("{}", community_view.get().community.id.0)
+      />
+      <input type="hidden" name="follow" value=move || format!("{}", follow())/>
+      <input type="hidden" name="return_to" value=return_to/>
+      <button type="submit" class=move || if follow() { "btn btn-primary" } else { "btn" }>
+        {move || match community_view.get().subscribed {
+            SubscribedType::Subscribed => t!(i18n, unsubscribe)().to_string(),
+            SubscribedType::Pending => t!(i18n, subscribe_pending)().to_string(),
+            SubscribedType::NotSubscribed => t!(i18n, subscribe)().to_string(),
+        }}
+
+      </button>
+    </ActionForm>
+  }
+}
@@ -1,4 +1,4 @@
-use crate::{errors::LemmyAppError, i18n::*, lemmy_client::*};
+use crate::{i18n::*, lemmy_client::*, notifications::use_notifications};
 use lemmy_api_common::{
   community::*,
   lemmy_db_schema::{ListingType, SortType},
@@ -11,7 +11,8 @@ use leptos_router::*;
 pub fn Trending() -> impl IntoView {
   let _i18n = use_i18n();
 
-  let error = expect_context::<RwSignal<Option<LemmyAppError>>>();
+  let notifications = use_notifications();
+  let client = store_value(use_lemmy_client());
 
   let trending = create_resource(
     move || (),
@@ -24,12 +25,12 @@ pub fn Trending() -> impl IntoView {
         page: None,
       };
 
-      let result = LemmyClient.list_communities(form).await;
+      let result = client.get_value().list_communities(form).await;
 
       match result {
         Ok(o) => Some(o),
         Err(e) => {
-          error.set(Some(e));
+          notifications.error(e);
           None
         }
       }
@@ -0,0 +1,320 @@
+use crate::{
+  errors::{LemmyAppError, LemmyAppResult},
+  i18n::*,
+  lemmy_client::*,
+  notifications::use_notifications,
+  redirect::use_return_to,
+  ui::components::{
+    common::nav::use_unread_count,
+    inbox::inbox_item::{InboxItem, InboxItemView},
+  },
+};
+#[cfg(feature = "ssr")]
+use crate::{flash::finish_form, redirect::return_path};
+use lemmy_api_common::{
+  lemmy_db_schema::CommentSortType,
+  person::{GetPersonMentions, GetReplies, GetRepliesResponse},
+  private_message::GetPrivateMessages,
+  site::GetSiteResponse,
+};
+use leptos::*;
+use leptos_router::*;
+use web_sys::SubmitEvent;
+
+const PAGE_LIMIT: i64 = 20;
+
+#[server(MarkAllAsReadFn, "/serverfn")]
+pub async fn mark_all_as_read_fn(
+  return_to: Option<String>,
+) -> Result<GetRepliesResponse, ServerFnError<LemmyAppError>> {
+  let result = use_lemmy_client().mark_all_as_read().await;
+
+  let back = return_path(return_to, None).await;
+
+  finish_form(result, &back, None).await
+}
+
+/// The tabs of the inbox, kept in the `view` query parameter.
+#[derive(Clone, Copy, PartialEq)]
+enum InboxView {
+  All,
+  Replies,
+  Mentions,
+  Messages,
+}
+
+impl InboxView {
+  fn from_query(view: Option<&String>) -> Self {
+    match view.map(String::as_str) {
+      Some("replies") => Self::Replies,
+      Some("mentions") => Self::Mentions,
+      Some("messages") => Self::Messages,
+      _ => Self::All,
+    }
+  }
+
+  fn as_query(self) -> &'static str {
+    match self {
+      Self::All => "all",
+      Self::Replies => "replies",
+      Self::Mentions => "mentions",
+      Self::Messages => "messages",
+    }
+  }
+}
+
+async fn load_inbox(
+  client: LemmyClient,
+  view: InboxView,
+  unread_only: bool,
+  page: i64,
+) -> LemmyAppResult<(Vec<InboxItem>, bool)> {
+  let mut items = vec![];
+  // a kind that fills its page probably has another one
+  let mut more = false;
+
+  if view == InboxView::All || view == InboxView::Replies {
+    let form = GetReplies {
+      sort: Some(CommentSortType::New),
+      page: Some(page),
+      limit: Some(PAGE_LIMIT),
+      unread_only: Some(unread_only),
+    };
+    let replies = client.get_replies(form).await?.replies;
+    more |= replies.len() as i64 >= PAGE_LIMIT;
+    items.extend(replies.into_iter().map(InboxItem::Reply));
+  }
+
+  if view == InboxView::All || view == InboxView::Mentions {
+    let form = GetPersonMentions {
+      sort: Some(CommentSortType::New),
+      page: Some(page),
+      limit: Some(PAGE_LIMIT),
+      unread_only: Some(unread_only),
+    };
+    let mentions = client.get_person_mentions(form).await?.mentions;
+    more |= mentions.len() as i64 >= PAGE_LIMIT;
+    items.extend(mentions.into_iter().map(InboxItem::Mention));
+  }
+
+  if view == InboxView::All || view == InboxView::Messages {
+    let form = GetPrivateMessages {
+      unread_only: Some(unread_only),
+      page: Some(page),
+      limit: Some(PAGE_LIMIT),
+      ..Default::default()
+    };
+    let messages = client.get_private_messages(form).await?.private_messages;
+    more |= messages.len() as i64 >= PAGE_LIMIT;
+    items.extend(messages.into_iter().map(InboxItem::Message));
+  }
+
+  items.sort_by_key(|i| std::cmp::Reverse(i.published()));
+
+  Ok((items, more))
+}
+
+#[component]
+pub fn InboxActivity(
+  site_signal: RwSignal<Option<Result<GetSiteResponse, LemmyAppError>>>,
+) -> impl IntoView {
+  let i18n = use_i18n();
+
+  let notifications = use_notifications();
+  let query = use_query_map();
+  let user = expect_context::<RwSignal<Option<bool>>>();
+  let client = store_value(use_lemmy_client());
+  let return_to = use_return_to();
+
+  let view_func = move || InboxView::from_query(query.get().get("view"));
+
+  // unread only, unless asked for everything
+  let unread_func = move || query.get().get("unread").map_or(true, |u| u != "false");
+
+  let page_func = move || {
+    query
+      .get()
+      .get("page")
+      .and_then(|p| p.parse::<i64>().ok())
+      .filter(|p| *p > 0)
+      .unwrap_or(1)
+  };
+
+  let my_person_id = move || {
+    site_signal
+      .get()
+      .and_then(Result::ok)
+      .and_then(|s| s.my_user)
+      .map(|m| m.local_user_view.person.id)
+  };
+
+  let inbox = create_resource(
+    move || (user.get(), view_func(), unread_func(), page_func()),
+    move |(_user, view, unread_only, page)| async move {
+      load_inbox(client.get_value(), view, unread_only, page).await
+    },
+  );
+
+  let unread_count = use_unread_count();
+  let mark_all_action = create_server_action::<MarkAllAsReadFn>();
+
+  let on_mark_all_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let result = client.get_value().mark_all_as_read().await;
+
+        match result {
+          Ok(_o) => {
+            inbox.refetch();
+            unread_count.refetch();
+          }
+          Err(e) => {
+            notifications.error(e);
+          }
+        }
+      },
+    );
+  };
+
+  let tab_href = move |view: InboxView| {
+    let mut query_params = query.get();
+    query_params.insert("view".into(), view.as_query().into());
+    query_params.remove("page");
+    query_params.to_query_string()
+  };
+
+  let tab_class = move |view: InboxView| {
+    format!(
+      "tab {}",
+      if view == view_func() {
+        "tab-active"
+      } else {
+        ""
+      }
+    )
+  };
+
+  let unread_href = move |unread: bool| {
+    let mut query_params = query.get();
+    query_params.insert("unread".into(), unread.to_string());
+    query_params.remove("page");
+    query_params.to_query_string()
+  };
+
+  let unread_class = move |unread: bool| {
+    format!(
+      "btn join-item {}",
+      if unread == unread_func() {
+        "btn-active"
+      } else {
+        ""
+      }
+    )
+  };
+
+  let page_href = move |page: i64| {
+    let mut query_params = query.get();
+    query_params.insert("page".into(), page.to_string());
+    query_params.to_query_string()
+  };
+
+  view! {
+    <main class="mx-auto">
+      <h2 class="p-6 text-4xl">{t!(i18n, inbox)}</h2>
+      <div class="flex flex-wrap items-center gap-3 px-6 mb-3">
+        <div class="join">
+          <A href=move || unread_href(true) class=move || unread_class(true)>
+            {t!(i18n, unread)}
+          </A>
+          <A href=move || unread_href(false) class=move || unread_class(false)>
+            {t!(i18n, all)}
+          </A>
+        </div>
+        <div role="tablist" class="tabs tabs-boxed">
+          <A
+            role="tab"
+            href=move || tab_href(InboxView::All)
+            class=move || tab_class(InboxView::All)
+          >
+            {t!(i18n, all)}
+          </A>
+          <A
+            role="tab"
+            href=move || tab_href(InboxView::Replies)
+            class=move || tab_class(InboxView::Replies)
+          >
+            {t!(i18n, replies)}
+          </A>
+          <A
+            role="tab"
+            href=move || tab_href(InboxView::Mentions)
+            class=move || tab_class(InboxView::Mentions)
+          >
+            {t!(i18n, mentions)}
+          </A>
+          <A
+            role="tab"
+            href=move || tab_href(InboxView::Messages)
+            class=move || tab_class(InboxView::Messages)
+          >
+            {t!(i18n, messages)}
+          </A>
+        </div>
+        <A href="/messages" class="btn">
+          {t!(i18n, conversations)}
+        </A>
+        <ActionForm action=mark_all_action on:submit=on_mark_all_submit>
+          <input type="hidden" name="return_to" value=return_to/>
+          <button type="submit" class="btn">
+            {t!(i18n, mark_all_as_read)}
+          </button>
+        </ActionForm>
+      </div>
+      <Transition fallback=|| {}>
+        {move || {
+            // not being logged in fails the whole page, see `routed_error_page`
+            inbox
+                .get()
+                .map(|res| {
+                    res.map(|(items, more)| {
+                        let page = page_func();
+                        let my_person_id = my_person_id();
+                        view! {
+                          <ul class="px-6">
+                            {items
+                                .into_iter()
+                                .map(|item| view! { <InboxItemView item my_person_id/> })
+                                .collect_view()}
+                          </ul>
+                          <div class="px-6">
+                            {(page > 1)
+                                .then(|| {
+                                    view! {
+                                      <A href=page_href(page - 1) class="btn">
+                                        "Prev"
+                                      </A>
+                                    }
+                                })}
+                            " "
+                            {more
+                                .then(|| {
+                                    view! {
+                                      <A href=page_href(page + 1) class="btn">
+                                        "Next"
+                                      </A>
+                                    }
+                                })}
+
+                          </div>
+                        }
+                    })
+                })
+        }}
+
+      </Transition>
+    </main>
+  }
+}
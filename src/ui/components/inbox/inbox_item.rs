@@ -0,0 +1,411 @@
+use crate::{
+  errors::LemmyAppError,
+  i18n::*,
+  lemmy_client::*,
+  notifications::use_notifications,
+  redirect::use_return_to,
+  ui::components::{comment::reply_form::ReplyForm, common::nav::use_unread_count},
+};
+#[cfg(feature = "ssr")]
+use crate::{flash::finish_form, redirect::return_path};
+use lemmy_api_common::{
+  lemmy_db_schema::newtypes::PersonId,
+  lemmy_db_views::structs::PrivateMessageView,
+  lemmy_db_views_actor::structs::{CommentReplyView, PersonMentionView},
+  person::{
+    CommentReplyResponse,
+    MarkCommentReplyAsRead,
+    MarkPersonMentionAsRead,
+    PersonMentionResponse,
+  },
+  private_message::{MarkPrivateMessageAsRead, PrivateMessageResponse},
+};
+use leptos::*;
+use leptos_router::*;
+use web_sys::SubmitEvent;
+
+#[server(MarkReplyAsReadFn, "/serverfn")]
+pub async fn mark_reply_as_read_fn(
+  comment_reply_id: i32,
+  read: bool,
+  return_to: Option<String>,
+) -> Result<CommentReplyResponse, ServerFnError<LemmyAppError>> {
+  use lemmy_api_common::lemmy_db_schema::newtypes::CommentReplyId;
+
+  let form = MarkCommentReplyAsRead {
+    comment_reply_id: CommentReplyId(comment_reply_id),
+    read,
+  };
+  let result = use_lemmy_client().mark_reply_as_read(form).await;
+
+  let back = return_path(return_to, None).await;
+
+  finish_form(result, &back, None).await
+}
+
+#[server(MarkMentionAsReadFn, "/serverfn")]
+pub async fn mark_mention_as_read_fn(
+  person_mention_id: i32,
+  read: bool,
+  return_to: Option<String>,
+) -> Result<PersonMentionResponse, ServerFnError<LemmyAppError>> {
+  use lemmy_api_common::lemmy_db_schema::newtypes::PersonMentionId;
+
+  let form = MarkPersonMentionAsRead {
+    person_mention_id: PersonMentionId(person_mention_id),
+    read,
+  };
+  let result = use_lemmy_client().mark_person_mention_as_read(form).await;
+
+  let back = return_path(return_to, None).await;
+
+  finish_form(result, &back, None).await
+}
+
+#[server(MarkMessageAsReadFn, "/serverfn")]
+pub async fn mark_message_as_read_fn(
+  private_message_id: i32,
+  read: bool,
+  return_to: Option<String>,
+) -> Result<PrivateMessageResponse, ServerFnError<LemmyAppError>> {
+  use lemmy_api_common::lemmy_db_schema::newtypes::PrivateMessageId;
+
+  let form = MarkPrivateMessageAsRead {
+    private_message_id: PrivateMessageId(private_message_id),
+    read,
+  };
+  let result = use_lemmy_client().mark_private_message_as_read(form).await;
+
+  let back = return_path(return_to, None).await;
+
+  finish_form(result, &back, None).await
+}
+
+/// Anything that lands in the inbox.
+#[derive(Clone)]
+pub enum InboxItem {
+  Reply(CommentReplyView),
+  Mention(PersonMentionView),
+  Message(PrivateMessageView),
+}
+
+impl InboxItem {
+  /// Unique across the kinds of items, and the anchor of the item on the page.
+  pub fn key(&self) -> String {
+    match self {
+      InboxItem::Reply(r) => format!("reply-{}", r.comment_reply.id.0),
+      InboxItem::Mention(m) => format!("mention-{}", m.person_mention.id.0),
+      InboxItem::Message(m) => format!("message-{}", m.private_message.id.0),
+    }
+  }
+
+  /// When the item arrived, in milliseconds since the epoch, to put the kinds in one order.
+  pub fn published(&self) -> i64 {
+    match self {
+      InboxItem::Reply(r) => r.comment_reply.published.timestamp_millis(),
+      InboxItem::Mention(m) => m.person_mention.published.timestamp_millis(),
+      InboxItem::Message(m) => m.private_message.published.timestamp_millis(),
+    }
+  }
+}
+
+#[component]
+pub fn InboxItemView(item: InboxItem, my_person_id: Option<PersonId>) -> impl IntoView {
+  let key = item.key();
+
+  view! {
+    <li id=key class="card bg-base-300 text-base-content mb-3">
+      <div class="card-body">
+        {match item {
+            InboxItem::Reply(r) => view! { <ReplyItem reply=r/> }.into_view(),
+            InboxItem::Mention(m) => view! { <MentionItem mention=m/> }.into_view(),
+            InboxItem::Message(m) => view! { <MessageItem message=m my_person_id/> }.into_view(),
+        }}
+
+      </div>
+    </li>
+  }
+}
+
+#[component]
+fn ReplyItem(reply: CommentReplyView) -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let client = store_value(use_lemmy_client());
+  let unread_count = use_unread_count();
+  let return_to = use_return_to();
+
+  let comment_reply_id = reply.comment_reply.id;
+  let read = create_rw_signal(reply.comment_reply.read);
+
+  let mark_action = create_server_action::<MarkReplyAsReadFn>();
+
+  let on_mark_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let form = MarkCommentReplyAsRead {
+          comment_reply_id,
+          read: !read.get_untracked(),
+        };
+
+        let result = client.get_value().mark_reply_as_read(form).await;
+
+        match result {
+          Ok(o) => {
+            read.set(o.comment_reply_view.comment_reply.read);
+            unread_count.refetch();
+          }
+          Err(e) => {
+            notifications.error(e);
+          }
+        }
+      },
+    );
+  };
+
+  view! {
+    <CommentContext
+      creator_name=reply.creator.name
+      post_id=reply.post.id.0
+      post_name=reply.post.name
+      comment_id=reply.comment.id.0
+      content=reply.comment.content
+      published=reply.comment_reply.published.format("%Y-%m-%d %H:%M").to_string()
+      read
+    />
+    <div class="card-actions items-start">
+      <ActionForm action=mark_action on:submit=on_mark_submit>
+        <input type="hidden" name="comment_reply_id" value=format!("{}", comment_reply_id.0)/>
+        <input type="hidden" name="read" value=move || format!("{}", !read.get())/>
+        <input type="hidden" name="return_to" value=return_to/>
+        <button type="submit" class="btn btn-sm">
+          {move || {
+              if read.get() {
+                  t!(i18n, mark_as_unread)().to_string()
+              } else {
+                  t!(i18n, mark_as_read)().to_string()
+              }
+          }}
+
+        </button>
+      </ActionForm>
+      <ReplyForm post_id=reply.post.id parent_id=Some(reply.comment.id)/>
+    </div>
+  }
+}
+
+#[component]
+fn MentionItem(mention: PersonMentionView) -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let client = store_value(use_lemmy_client());
+  let unread_count = use_unread_count();
+  let return_to = use_return_to();
+
+  let person_mention_id = mention.person_mention.id;
+  let read = create_rw_signal(mention.person_mention.read);
+
+  let mark_action = create_server_action::<MarkMentionAsReadFn>();
+
+  let on_mark_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let form = MarkPersonMentionAsRead {
+          person_mention_id,
+          read: !read.get_untracked(),
+        };
+
+        let result = client.get_value().mark_person_mention_as_read(form).await;
+
+        match result {
+          Ok(o) => {
+            read.set(o.person_mention_view.person_mention.read);
+            unread_count.refetch();
+          }
+          Err(e) => {
+            notifications.error(e);
+          }
+        }
+      },
+    );
+  };
+
+  view! {
+    <CommentContext
+      creator_name=mention.creator.name
+      post_id=mention.post.id.0
+      post_name=mention.post.name
+      comment_id=mention.comment.id.0
+      content=mention.comment.content
+      published=mention.person_mention.published.format("%Y-%m-%d %H:%M").to_string()
+      read
+    />
+    <div class="card-actions items-start">
+      <ActionForm action=mark_action on:submit=on_mark_submit>
+        <input type="hidden" name="person_mention_id" value=format!("{}", person_mention_id.0)/>
+        <input type="hidden" name="read" value=move || format!("{}", !read.get())/>
+        <input type="hidden" name="return_to" value=return_to/>
+        <button type="submit" class="btn btn-sm">
+          {move || {
+              if read.get() {
+                  t!(i18n, mark_as_unread)().to_string()
+              } else {
+                  t!(i18n, mark_as_read)().to_string()
+              }
+          }}
+
+        </button>
+      </ActionForm>
+      <ReplyForm post_id=mention.post.id parent_id=Some(mention.comment.id)/>
+    </div>
+  }
+}
+
+// the part replies and mentions have in common, a comment somewhere in a post
+#[component]
+fn CommentContext(
+  creator_name: String,
+  post_id: i32,
+  post_name: String,
+  comment_id: i32,
+  content: String,
+  published: String,
+  read: RwSignal<bool>,
+) -> impl IntoView {
+  let i18n = use_i18n();
+
+  view! {
+    <p class="text-sm">
+      <A href=format!("/u/{creator_name}") class="font-bold">
+        {creator_name.clone()}
+      </A>
+      " "
+      {t!(i18n, in_post)}
+      " "
+      <A href=format!("/post/{post_id}#comment-{comment_id}") class="link link-accent">
+        {post_name}
+      </A>
+      " · "
+      {published}
+      " "
+      <Show when=move || !read.get()>
+        <span class="badge badge-primary">{t!(i18n, unread)}</span>
+      </Show>
+    </p>
+    // where a reply sent without javascript comes back to
+    <p class="whitespace-pre-wrap" id=format!("comment-{comment_id}")>
+      {content}
+    </p>
+  }
+}
+
+#[component]
+fn MessageItem(message: PrivateMessageView, my_person_id: Option<PersonId>) -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let client = store_value(use_lemmy_client());
+  let unread_count = use_unread_count();
+  let return_to = use_return_to();
+
+  let private_message_id = message.private_message.id;
+  let read = create_rw_signal(message.private_message.read);
+  // only the recipient marks a message as read
+  let received = Some(message.recipient.id) == my_person_id;
+
+  let mark_action = create_server_action::<MarkMessageAsReadFn>();
+
+  let on_mark_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let form = MarkPrivateMessageAsRead {
+          private_message_id,
+          read: !read.get_untracked(),
+        };
+
+        let result = client.get_value().mark_private_message_as_read(form).await;
+
+        match result {
+          Ok(o) => {
+            read.set(o.private_message_view.private_message.read);
+            unread_count.refetch();
+          }
+          Err(e) => {
+            notifications.error(e);
+          }
+        }
+      },
+    );
+  };
+
+  view! {
+    <p class="text-sm">
+      {if received {
+          t!(i18n, message_from)().to_string()
+      } else {
+          t!(i18n, message_to)().to_string()
+      }}
+      " "
+      <A
+        href=format!(
+            "/u/{}",
+            if received { &message.creator.name } else { &message.recipient.name },
+        )
+        class="font-bold"
+      >
+        {if received { message.creator.name.clone() } else { message.recipient.name.clone() }}
+      </A>
+      " · "
+      {message.private_message.published.format("%Y-%m-%d %H:%M").to_string()}
+      " "
+      <Show when=move || received && !read.get()>
+        <span class="badge badge-primary">{t!(i18n, unread)}</span>
+      </Show>
+    </p>
+    <p class="whitespace-pre-wrap">{message.private_message.content.clone()}</p>
+    <div class="card-actions">
+      <A
+        href=format!(
+            "/messages/{}",
+            if received { message.creator.id.0 } else { message.recipient.id.0 },
+        )
+        class="btn btn-sm"
+      >
+        {t!(i18n, reply)}
+      </A>
+      {received
+          .then(|| {
+              view! {
+                <ActionForm action=mark_action on:submit=on_mark_submit>
+                  <input
+                    type="hidden"
+                    name="private_message_id"
+                    value=format!("{}", private_message_id.0)
+                  />
+                  <input type="hidden" name="read" value=move || format!("{}", !read.get())/>
+                  <input type="hidden" name="return_to" value=return_to/>
+                  <button type="submit" class="btn btn-sm">
+                    {move || {
+                        if read.get() {
+                            t!(i18n, mark_as_unread)().to_string()
+                        } else {
+                            t!(i18n, mark_as_read)().to_string()
+                        }
+                    }}
+
+                  </button>
+                </ActionForm>
+              }
+          })}
+
+    </div>
+  }
+}
@@ -0,0 +1,2 @@
+pub mod inbox_activity;
+pub mod inbox_item;
@@ -1,11 +1,38 @@
-use crate::ui::components::login::login_form::LoginForm;
+use crate::{i18n::*, ui::components::login::login_form::LoginForm};
 use leptos::*;
+use leptos_router::*;
 
 #[component]
 pub fn LoginActivity() -> impl IntoView {
+  let i18n = use_i18n();
+
+  let query = use_query_map();
+  // where a password reset or an email verification leads once done
+  let password_changed = move || {
+    query
+      .get()
+      .get("password_changed")
+      .is_some_and(|p| p == "true")
+  };
+  let email_verified = move || {
+    query
+      .get()
+      .get("email_verified")
+      .is_some_and(|e| e == "true")
+  };
+
   view! {
     <main class="mx-auto max-w-screen-md p-3">
+      <Show when=password_changed>
+        <div class="alert alert-success mb-3">{t!(i18n, password_changed)}</div>
+      </Show>
+      <Show when=email_verified>
+        <div class="alert alert-success mb-3">{t!(i18n, email_verified)}</div>
+      </Show>
       <LoginForm/>
+      <A href="/password_reset" class="link block mt-6">
+        {t!(i18n, forgot_password)}
+      </A>
     </main>
   }
 }
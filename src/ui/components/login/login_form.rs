@@ -1,10 +1,25 @@
+#[cfg(feature = "ssr")]
+use crate::{
+  config::use_public_config,
+  cookie::set_auth_cookie,
+  errors::LemmyAppResult,
+  flash::finish_form,
+  lemmy_client::*,
+  ui::components::settings::settings_activity::set_ui_cookies,
+};
 use crate::{
-  cookie::set_cookie,
   errors::{LemmyAppError, LemmyAppErrorType},
+  flash::use_flash,
   i18n::*,
-  ui::components::common::text_input::{InputType, TextInput},
+  notifications::use_notifications,
+  ui::components::{
+    common::text_input::{InputType, TextInput},
+    settings::settings_activity::{saved_locale, saved_theme, BROWSER_DEFAULT},
+  },
 };
-use lemmy_api_common::person::{Login, LoginResponse};
+#[cfg(feature = "ssr")]
+use lemmy_api_common::person::LoginResponse;
+use lemmy_api_common::{lemmy_db_views::structs::LocalUserView, person::Login, LemmyErrorType};
 use leptos::*;
 use leptos_router::*;
 use web_sys::SubmitEvent;
@@ -19,14 +34,23 @@ fn validate_login(form: &Login) -> Option<LemmyAppErrorType> {
   None
 }
 
-async fn try_login(form: Login) -> Result<LoginResponse, LemmyAppError> {
+// the account has two-factor authentication on, so the login takes a code as well
+fn needs_totp(error_type: &LemmyAppErrorType) -> bool {
+  matches!(
+    error_type,
+    LemmyAppErrorType::ApiError(
+      LemmyErrorType::MissingTotpToken | LemmyErrorType::IncorrectTotpToken
+    )
+  )
+}
+
+#[cfg(feature = "ssr")]
+async fn try_login(client: LemmyClient, form: Login) -> Result<LoginResponse, LemmyAppError> {
   let val = validate_login(&form);
 
   match val {
     None => {
-      use crate::lemmy_client::*;
-
-      let result = LemmyClient.login(form).await;
+      let result = client.login(form).await;
 
       match result {
         Ok(LoginResponse { ref jwt, .. }) => {
@@ -50,109 +74,128 @@ async fn try_login(form: Login) -> Result<LoginResponse, LemmyAppError> {
 }
 
 #[server(LoginFn, "/serverfn")]
-pub async fn login(username_or_email: String, password: String) -> Result<(), ServerFnError> {
-  use leptos_actix::redirect;
-
+pub async fn login(
+  username_or_email: String,
+  password: String,
+  totp_2fa_token: Option<String>,
+) -> Result<Option<LocalUserView>, ServerFnError<LemmyAppError>> {
   let req = Login {
     username_or_email: username_or_email.into(),
     password: password.into(),
-    totp_2fa_token: None,
+    totp_2fa_token: totp_2fa_token.filter(|t| !t.is_empty()),
   };
 
-  let result = try_login(req).await;
-
-  match result {
-    Ok(LoginResponse { jwt, .. }) => {
-      let r = set_cookie(
-        "jwt",
-        &jwt.unwrap_or_default().into_inner(),
-        &core::time::Duration::from_secs(604800),
-      )
-      .await;
-      match r {
-        Ok(_o) => {
-          redirect("/");
-          Ok(())
-        }
-        Err(e) => {
-          redirect(&format!("/login?error={}", serde_json::to_string(&e)?)[..]);
-          Ok(())
-        }
-      }
-    }
-    Err(e) => {
-      redirect(&format!("/login?error={}", serde_json::to_string(&e)?)[..]);
-      Ok(())
+  let result: LemmyAppResult<Option<LocalUserView>> = async {
+    let LoginResponse { jwt, .. } = try_login(use_lemmy_client(), req).await?;
+    let jwt = jwt.unwrap_or_default().into_inner();
+    set_auth_cookie(&jwt, &use_public_config().cookie.max_age()).await?;
+
+    // the theme and language saved in the account take over from here, a failure to fetch them
+    // leaves the ones of the browser
+    let local_user_view = use_lemmy_client()
+      .get_site_with_jwt(jwt)
+      .await
+      .ok()
+      .and_then(|s| s.my_user)
+      .map(|m| m.local_user_view);
+    if let Some(v) = &local_user_view {
+      let _ = set_ui_cookies(&v.local_user).await;
     }
+    Ok(local_user_view)
   }
+  .await;
+
+  // the hydrated app navigates by itself
+  finish_form(result, "/login", Some("/")).await
 }
 
 #[component]
 pub fn LoginForm() -> impl IntoView {
-  let _i18n = use_i18n();
+  let i18n = use_i18n();
 
-  let query = use_query_map();
-
-  let error = expect_context::<RwSignal<Option<LemmyAppError>>>();
+  let notifications = use_notifications();
   let user = expect_context::<RwSignal<Option<bool>>>();
+  let ui_theme = expect_context::<RwSignal<Option<String>>>();
 
   let name = create_rw_signal(String::new());
   let password = create_rw_signal(String::new());
+  let totp_2fa_token = create_rw_signal(String::new());
+  // the second step, once lemmy asked for a code
+  let ask_totp = create_rw_signal(false);
 
-  let login = create_server_action::<LoginFn>();
+  let login_action = create_server_action::<LoginFn>();
+  let navigate = store_value(use_navigate());
 
   let username_validation = create_rw_signal::<String>("".into());
   let password_validation = create_rw_signal::<String>("".into());
-
-  let ssr_error = move || query.with(|params| params.get("error").cloned());
-
-  if let Some(e) = ssr_error() {
-    let le = serde_json::from_str::<LemmyAppError>(&e[..]);
-
-    match le {
-      Ok(e) => match e.error_type {
-        LemmyAppErrorType::EmptyUsername => username_validation.set("input-error".to_string()),
-        LemmyAppErrorType::EmptyPassword => password_validation.set("input-error".to_string()),
-        _ => {}
-      },
-      Err(_) => {}
+  let totp_validation = create_rw_signal::<String>("".into());
+
+  // without javascript the second step starts over with all three fields
+  if let Some(e) = use_flash().error() {
+    match e.error_type {
+      LemmyAppErrorType::EmptyUsername => username_validation.set("input-error".to_string()),
+      LemmyAppErrorType::EmptyPassword => password_validation.set("input-error".to_string()),
+      LemmyAppErrorType::ApiError(LemmyErrorType::IncorrectTotpToken) => {
+        ask_totp.set(true);
+        totp_validation.set("input-error".to_string());
+      }
+      ref t if needs_totp(t) => ask_totp.set(true),
+      _ => {}
     }
   }
 
   let on_submit = move |ev: SubmitEvent| {
     ev.prevent_default();
-    error.set(None);
 
     create_local_resource(
       move || (name.get(), password.get()),
       move |(name, password)| async move {
+        let totp_2fa_token = ask_totp
+          .get_untracked()
+          .then(|| totp_2fa_token.get_untracked());
         let req = Login {
-          username_or_email: name.into(),
-          password: password.into(),
-          totp_2fa_token: None,
+          username_or_email: name.clone().into(),
+          password: password.clone().into(),
+          totp_2fa_token: totp_2fa_token.clone(),
+        };
+        let result = match validate_login(&req) {
+          Some(e) => Err(LemmyAppError::from(e)),
+          // only the server can set the HttpOnly session cookie
+          None => login(name, password, totp_2fa_token)
+            .await
+            .map_err(LemmyAppError::from),
         };
-        let result = try_login(req.clone()).await;
         match result {
-          Ok(LoginResponse { jwt: Some(jwt), .. }) => {
-            let _ = set_cookie(
-              "jwt",
-              &jwt.clone().into_inner(),
-              &core::time::Duration::from_secs(604800),
-            )
-            .await;
+          Ok(local_user_view) => {
+            if let Some(local_user) = local_user_view.map(|v| v.local_user) {
+              if let Some(theme) = saved_theme(&local_user) {
+                ui_theme.set(Some(theme));
+              } else if local_user.theme == BROWSER_DEFAULT {
+                ui_theme.set(None);
+              }
+              if let Some(locale) = saved_locale(&local_user) {
+                i18n.set_locale(locale);
+              }
+            }
             user.set(Some(true));
-            leptos_router::use_navigate()("/", Default::default());
-          }
-          Ok(LoginResponse { jwt: None, .. }) => {
-            error.set(Some(LemmyAppError {
-              error_type: LemmyAppErrorType::MissingToken,
-              content: String::default(),
-            }));
+            navigate.with_value(|navigate| navigate("/", Default::default()));
           }
           Err(e) => {
-            error.set(Some(e.clone()));
+            // the first time lemmy asks for a code is no mistake, only the code field shows
+            let first_totp_ask = needs_totp(&e.error_type) && !ask_totp.get_untracked();
+            if !first_totp_ask {
+              notifications.error(e.clone());
+            }
             password_validation.set("".to_string());
             username_validation.set("".to_string());
+            totp_validation.set("".to_string());
+
+            if needs_totp(&e.error_type) {
+              if !first_totp_ask {
+                totp_validation.set("input-error".to_string());
+              }
+              ask_totp.set(true);
+            }
 
             match e {
               LemmyAppError {
@@ -176,7 +219,7 @@ pub fn LoginForm() -> impl IntoView {
   };
 
   view! {
-    <ActionForm class="space-y-3" action=login on:submit=on_submit>
+    <ActionForm class="space-y-3" action=login_action on:submit=on_submit>
       <TextInput
         id="username"
         name="username_or_email"
@@ -191,6 +234,16 @@ pub fn LoginForm() -> impl IntoView {
         input_type=InputType::Password
         label="Password"
       />
+      <Show when=move || ask_totp.get()>
+        <TextInput
+          id="totp_2fa_token"
+          name="totp_2fa_token"
+          required=true
+          validation_class=totp_validation.into()
+          on_input=move |s| update!(| totp_2fa_token | * totp_2fa_token = s)
+          label=move || t!(i18n, totp_code)().to_string()
+        />
+      </Show>
       <button class="btn btn-lg" type="submit">
         "Login"
       </button>
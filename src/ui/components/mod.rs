@@ -1,6 +1,14 @@
 pub mod comment;
 pub mod common;
 pub mod communities;
+pub mod community;
 pub mod home;
+pub mod inbox;
 pub mod login;
+pub mod password_reset;
+pub mod person;
 pub mod post;
+pub mod private_message;
+pub mod settings;
+pub mod signup;
+pub mod verify_email;
@@ -0,0 +1,2 @@
+pub mod password_change_activity;
+pub mod password_reset_activity;
@@ -0,0 +1,163 @@
+use crate::{
+  errors::{LemmyAppError, LemmyAppErrorType},
+  flash::use_flash,
+  i18n::*,
+  lemmy_client::*,
+  notifications::use_notifications,
+  redirect::use_return_to,
+  ui::components::common::text_input::{InputType, TextInput},
+};
+#[cfg(feature = "ssr")]
+use crate::{flash::finish_form, redirect::return_path};
+use lemmy_api_common::{person::PasswordChangeAfterReset, SuccessResponse};
+use leptos::*;
+use leptos_router::*;
+use web_sys::SubmitEvent;
+
+async fn try_password_change(
+  client: LemmyClient,
+  form: PasswordChangeAfterReset,
+) -> Result<SuccessResponse, LemmyAppError> {
+  if form.password.is_empty() {
+    return Err(LemmyAppErrorType::EmptyPassword.into());
+  }
+  if *form.password != *form.password_verify {
+    return Err(LemmyAppErrorType::PasswordMismatch.into());
+  }
+
+  client.password_change_after_reset(form).await
+}
+
+/// Sets the password chosen with the token of a reset link. Errors go back to the page of that
+/// link, through `return_to`.
+#[server(PasswordChangeFn, "/serverfn")]
+pub async fn password_change_fn(
+  token: String,
+  password: String,
+  password_verify: String,
+  return_to: Option<String>,
+) -> Result<(), ServerFnError<LemmyAppError>> {
+  let form = PasswordChangeAfterReset {
+    token: token.into(),
+    password: password.into(),
+    password_verify: password_verify.into(),
+  };
+  let result = try_password_change(use_lemmy_client(), form).await;
+
+  let back = return_path(return_to, None).await;
+
+  finish_form(
+    result.map(|_| ()),
+    &back,
+    Some("/login?password_changed=true"),
+  )
+  .await
+}
+
+#[component]
+pub fn PasswordChangeActivity() -> impl IntoView {
+  let i18n = use_i18n();
+
+  let params = use_params_map();
+  let token = move || params.get().get("token").cloned().unwrap_or_default();
+
+  view! {
+    <main class="mx-auto max-w-screen-md p-3">
+      <h2 class="text-4xl mb-3">{t!(i18n, choose_new_password)}</h2>
+      {move || view! { <PasswordChangeForm token=token()/> }}
+    </main>
+  }
+}
+
+#[component]
+fn PasswordChangeForm(token: String) -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let client = store_value(use_lemmy_client());
+  let return_to = use_return_to();
+
+  let password = create_rw_signal(String::new());
+  let password_verify = create_rw_signal(String::new());
+
+  let change_action = create_server_action::<PasswordChangeFn>();
+  let navigate = store_value(use_navigate());
+
+  let password_validation = create_rw_signal::<String>("".into());
+  let password_verify_validation = create_rw_signal::<String>("".into());
+
+  let mark = move |error_type: &LemmyAppErrorType| match error_type {
+    LemmyAppErrorType::EmptyPassword => password_validation.set("input-error".to_string()),
+    LemmyAppErrorType::PasswordMismatch => {
+      password_verify_validation.set("input-error".to_string())
+    }
+    _ => {}
+  };
+
+  if let Some(e) = use_flash().error() {
+    mark(&e.error_type);
+  }
+
+  let form_token = token.clone();
+  let on_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    let token = form_token.clone();
+    create_local_resource(
+      move || (),
+      move |()| {
+        let token = token.clone();
+        async move {
+          let form = PasswordChangeAfterReset {
+            token: token.into(),
+            password: password.get_untracked().into(),
+            password_verify: password_verify.get_untracked().into(),
+          };
+
+          let result = try_password_change(client.get_value(), form).await;
+
+          match result {
+            Ok(_o) => {
+              navigate.with_value(|navigate| {
+                navigate("/login?password_changed=true", Default::default())
+              });
+            }
+            Err(e) => {
+              password_validation.set("".to_string());
+              password_verify_validation.set("".to_string());
+              mark(&e.error_type);
+              notifications.error(e);
+            }
+          }
+        }
+      },
+    );
+  };
+
+  view! {
+    <ActionForm class="space-y-3" action=change_action on:submit=on_submit>
+      <input type="hidden" name="token" value=token/>
+      <input type="hidden" name="return_to" value=return_to/>
+      <TextInput
+        id="password"
+        name="password"
+        required=true
+        validation_class=password_validation.into()
+        on_input=move |s| update!(| password | * password = s)
+        input_type=InputType::Password
+        label=move || t!(i18n, new_password)().to_string()
+      />
+      <TextInput
+        id="password_verify"
+        name="password_verify"
+        required=true
+        validation_class=password_verify_validation.into()
+        on_input=move |s| update!(| password_verify | * password_verify = s)
+        input_type=InputType::Password
+        label=move || t!(i18n, new_password_verify)().to_string()
+      />
+      <button class="btn btn-lg" type="submit">
+        {t!(i18n, password_reset)}
+      </button>
+    </ActionForm>
+  }
+}
@@ -0,0 +1,130 @@
+#[cfg(feature = "ssr")]
+use crate::flash::finish_form;
+use crate::{
+  errors::{LemmyAppError, LemmyAppErrorType},
+  flash::use_flash,
+  i18n::*,
+  lemmy_client::*,
+  notifications::use_notifications,
+  ui::components::common::text_input::TextInput,
+};
+use lemmy_api_common::{person::PasswordReset, SuccessResponse};
+use leptos::*;
+use leptos_router::*;
+use web_sys::SubmitEvent;
+
+async fn try_password_reset(
+  client: LemmyClient,
+  form: PasswordReset,
+) -> Result<SuccessResponse, LemmyAppError> {
+  if form.email.trim().is_empty() {
+    return Err(LemmyAppErrorType::EmptyEmail.into());
+  }
+
+  client.password_reset(form).await
+}
+
+/// Asks lemmy to email a link for choosing a new password.
+#[server(PasswordResetFn, "/serverfn")]
+pub async fn password_reset_fn(email: String) -> Result<(), ServerFnError<LemmyAppError>> {
+  let form = PasswordReset {
+    email: email.into(),
+  };
+  let result = try_password_reset(use_lemmy_client(), form).await;
+
+  finish_form(
+    result.map(|_| ()),
+    "/password_reset",
+    Some("/password_reset?sent=true"),
+  )
+  .await
+}
+
+#[component]
+pub fn PasswordResetActivity() -> impl IntoView {
+  let i18n = use_i18n();
+
+  let query = use_query_map();
+  // lemmy does not tell whether the address is known, so neither does this
+  let sent = move || query.get().get("sent").is_some_and(|s| s == "true");
+
+  view! {
+    <main class="mx-auto max-w-screen-md p-3">
+      <h2 class="text-4xl mb-3">{t!(i18n, password_reset)}</h2>
+      {move || {
+          if sent() {
+              view! { <p>{t!(i18n, password_reset_sent)}</p> }.into_view()
+          } else {
+              view! { <PasswordResetForm/> }.into_view()
+          }
+      }}
+
+    </main>
+  }
+}
+
+#[component]
+fn PasswordResetForm() -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let client = store_value(use_lemmy_client());
+
+  let email = create_rw_signal(String::new());
+
+  let reset_action = create_server_action::<PasswordResetFn>();
+  let navigate = store_value(use_navigate());
+
+  let validation = create_rw_signal::<String>("".into());
+
+  if let Some(LemmyAppError {
+    error_type: LemmyAppErrorType::EmptyEmail,
+    ..
+  }) = use_flash().error()
+  {
+    validation.set("input-error".to_string());
+  }
+
+  let on_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let form = PasswordReset {
+          email: email.get_untracked().into(),
+        };
+
+        let result = try_password_reset(client.get_value(), form).await;
+
+        match result {
+          Ok(_o) => {
+            navigate
+              .with_value(|navigate| navigate("/password_reset?sent=true", Default::default()));
+          }
+          Err(e) => {
+            if e.error_type == LemmyAppErrorType::EmptyEmail {
+              validation.set("input-error".to_string());
+            }
+            notifications.error(e);
+          }
+        }
+      },
+    );
+  };
+
+  view! {
+    <ActionForm class="space-y-3" action=reset_action on:submit=on_submit>
+      <TextInput
+        id="email"
+        name="email"
+        required=true
+        validation_class=validation.into()
+        on_input=move |s| update!(| email | * email = s)
+        label=move || t!(i18n, email)().to_string()
+      />
+      <button class="btn btn-lg" type="submit">
+        {t!(i18n, send_reset_link)}
+      </button>
+    </ActionForm>
+  }
+}
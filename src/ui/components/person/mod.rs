@@ -0,0 +1,2 @@
+pub mod person_actions;
+pub mod profile_activity;
@@ -0,0 +1,321 @@
+use crate::{
+  errors::LemmyAppError,
+  i18n::*,
+  lemmy_client::*,
+  notifications::use_notifications,
+  redirect::use_return_to,
+  ui::components::post::post_listing::BlockUserFn,
+};
+#[cfg(feature = "ssr")]
+use crate::{flash::finish_form, redirect::return_path};
+use lemmy_api_common::{
+  lemmy_db_views::structs::LocalUserView,
+  lemmy_db_views_actor::structs::PersonView,
+  person::{BanPerson, BanPersonResponse, BlockPerson, PurgePerson},
+  site::GetSiteResponse,
+  SuccessResponse,
+};
+use leptos::*;
+use leptos_router::*;
+use web_sys::SubmitEvent;
+
+/// When a ban of `days` days from now ends, in seconds since the epoch as the api wants it. No
+/// days, or none at all, is a permanent ban.
+fn ban_expires(days: Option<i64>) -> Option<i64> {
+  days
+    .filter(|d| *d > 0)
+    .map(|d| (now_millis() / 1000) as i64 + d * 24 * 60 * 60)
+}
+
+#[server(BanPersonFn, "/serverfn")]
+pub async fn ban_person_fn(
+  person_id: i32,
+  ban: bool,
+  reason: Option<String>,
+  days: Option<i64>,
+  remove_data: Option<bool>,
+  return_to: Option<String>,
+) -> Result<BanPersonResponse, ServerFnError<LemmyAppError>> {
+  use lemmy_api_common::lemmy_db_schema::newtypes::PersonId;
+
+  let form = BanPerson {
+    person_id: PersonId(person_id),
+    ban,
+    remove_data,
+    reason: reason.filter(|r| !r.is_empty()),
+    expires: ban_expires(days),
+  };
+  let result = use_lemmy_client().ban_person(form).await;
+
+  let back = return_path(return_to, None).await;
+
+  finish_form(result, &back, None).await
+}
+
+#[server(PurgePersonFn, "/serverfn")]
+pub async fn purge_person_fn(
+  person_id: i32,
+  reason: Option<String>,
+  return_to: Option<String>,
+) -> Result<SuccessResponse, ServerFnError<LemmyAppError>> {
+  use lemmy_api_common::lemmy_db_schema::newtypes::PersonId;
+
+  let form = PurgePerson {
+    person_id: PersonId(person_id),
+    reason: reason.filter(|r| !r.is_empty()),
+  };
+  let result = use_lemmy_client().purge_person(form).await;
+
+  // the profile is gone along with the person
+  finish_form(result, &return_path(return_to, None).await, Some("/")).await
+}
+
+/// What the logged in user can do to the person of a profile: block or unblock them and write to
+/// them, and as an admin ban or purge them. Nothing for visitors or on one's own profile.
+#[component]
+pub fn PersonActions(
+  person_view: PersonView,
+  site_signal: RwSignal<Option<Result<GetSiteResponse, LemmyAppError>>>,
+) -> impl IntoView {
+  let person_id = person_view.person.id;
+
+  let me = move || {
+    site_signal
+      .get()
+      .and_then(Result::ok)
+      .and_then(|s| s.my_user)
+      .filter(|m| m.local_user_view.person.id != person_id)
+  };
+
+  move || {
+    me().map(|m| {
+      let blocked = m.person_blocks.iter().any(|b| b.target.id == person_id);
+
+      view! {
+        <PersonActionForms
+          person_view=person_view.clone()
+          local_user_view=m.local_user_view
+          blocked
+        />
+      }
+    })
+  }
+}
+
+#[component]
+fn PersonActionForms(
+  person_view: PersonView,
+  local_user_view: LocalUserView,
+  blocked: bool,
+) -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let client = store_value(use_lemmy_client());
+  let navigate = store_value(use_navigate());
+  let return_to = use_return_to();
+
+  let person_id = person_view.person.id;
+  let blocked = create_rw_signal(blocked);
+  let banned = create_rw_signal(person_view.person.banned);
+  // admins can't be banned or purged, they have to be removed from the admins first
+  let can_moderate = local_user_view.local_user.admin && !person_view.is_admin;
+
+  let block_user_action = create_server_action::<BlockUserFn>();
+
+  let on_block_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let form = BlockPerson {
+          person_id,
+          block: !blocked.get_untracked(),
+        };
+
+        let result = client.get_value().block_user(form).await;
+
+        match result {
+          Ok(o) => {
+            blocked.set(o.blocked);
+            if o.blocked {
+              notifications.success(t!(i18n, user_blocked)().to_string());
+            } else {
+              notifications.success(t!(i18n, user_unblocked)().to_string());
+            }
+          }
+          Err(e) => {
+            notifications.error(e);
+          }
+        }
+      },
+    );
+  };
+
+  let ban_person_action = create_server_action::<BanPersonFn>();
+  let ban_reason = RwSignal::new(String::new());
+  let ban_days = RwSignal::new(String::new());
+  let remove_data = RwSignal::new(false);
+
+  let on_ban_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let ban = !banned.get_untracked();
+        let form = BanPerson {
+          person_id,
+          ban,
+          remove_data: ban.then(|| remove_data.get_untracked()),
+          reason: Some(ban_reason.get_untracked()).filter(|r| !r.is_empty()),
+          expires: ban_expires(ban_days.get_untracked().parse::<i64>().ok()),
+        };
+
+        let result = client.get_value().ban_person(form).await;
+
+        match result {
+          Ok(o) => {
+            banned.set(o.banned);
+            if o.banned {
+              notifications.success(t!(i18n, user_banned)().to_string());
+            } else {
+              notifications.success(t!(i18n, user_unbanned)().to_string());
+            }
+          }
+          Err(e) => {
+            notifications.error(e);
+          }
+        }
+      },
+    );
+  };
+
+  let purge_person_action = create_server_action::<PurgePersonFn>();
+  let purge_reason = RwSignal::new(String::new());
+
+  let on_purge_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let form = PurgePerson {
+          person_id,
+          reason: Some(purge_reason.get_untracked()).filter(|r| !r.is_empty()),
+        };
+
+        let result = client.get_value().purge_person(form).await;
+
+        match result {
+          Ok(_o) => {
+            notifications.success(t!(i18n, user_purged)().to_string());
+            navigate.with_value(|navigate| navigate("/", Default::default()));
+          }
+          Err(e) => {
+            notifications.error(e);
+          }
+        }
+      },
+    );
+  };
+
+  view! {
+    <div class="flex flex-wrap gap-2">
+      <A class="btn btn-primary" href=format!("/create_private_message/{}", person_id.0)>
+        {t!(i18n, send_message)}
+      </A>
+      <ActionForm action=block_user_action on:submit=on_block_submit>
+        <input type="hidden" name="person_id" value=format!("{}", person_id.0)/>
+        <input type="hidden" name="block" value=move || format!("{}", !blocked.get())/>
+        <input type="hidden" name="return_to" value=return_to/>
+        <button type="submit" class="btn">
+          {move || {
+              if blocked.get() {
+                  t!(i18n, unblock_user)().to_string()
+              } else {
+                  t!(i18n, block_user)().to_string()
+              }
+          }}
+
+        </button>
+      </ActionForm>
+    </div>
+    {can_moderate
+        .then(|| {
+            view! {
+              <div class="flex flex-wrap gap-2 mt-3">
+                <ActionForm
+                  action=ban_person_action
+                  on:submit=on_ban_submit
+                  class="flex flex-wrap gap-2"
+                >
+                  <input type="hidden" name="person_id" value=format!("{}", person_id.0)/>
+                  <input type="hidden" name="ban" value=move || format!("{}", !banned.get())/>
+                  <input type="hidden" name="return_to" value=return_to/>
+                  <input
+                    class="input input-bordered input-sm"
+                    type="text"
+                    name="reason"
+                    placeholder=move || t!(i18n, reason)().to_string()
+                    on:input=move |e| ban_reason.set(event_target_value(&e))
+                  />
+                  <Show when=move || !banned.get()>
+                    <input
+                      class="input input-bordered input-sm w-28"
+                      type="number"
+                      min="1"
+                      name="days"
+                      placeholder=move || t!(i18n, ban_days)().to_string()
+                      on:input=move |e| ban_days.set(event_target_value(&e))
+                    />
+                    <label class="label cursor-pointer gap-2">
+                      <input
+                        type="checkbox"
+                        class="checkbox checkbox-sm"
+                        name="remove_data"
+                        value="true"
+                        on:change=move |e| remove_data.set(event_target_checked(&e))
+                      />
+                      <span class="label-text">{t!(i18n, remove_content)}</span>
+                    </label>
+                  </Show>
+                  <button type="submit" class="btn btn-sm btn-warning">
+                    {move || {
+                        if banned.get() {
+                            t!(i18n, unban_user)().to_string()
+                        } else {
+                            t!(i18n, ban_user)().to_string()
+                        }
+                    }}
+
+                  </button>
+                </ActionForm>
+                <ActionForm
+                  action=purge_person_action
+                  on:submit=on_purge_submit
+                  class="flex flex-wrap gap-2"
+                >
+                  <input type="hidden" name="person_id" value=format!("{}", person_id.0)/>
+                  <input type="hidden" name="return_to" value=return_to/>
+                  <input
+                    class="input input-bordered input-sm"
+                    type="text"
+                    name="reason"
+                    placeholder=move || t!(i18n, reason)().to_string()
+                    on:input=move |e| purge_reason.set(event_target_value(&e))
+                  />
+                  // the browser asks for this before submitting, with or without javascript
+                  <label class="label cursor-pointer gap-2">
+                    <input type="checkbox" class="checkbox checkbox-sm" required/>
+                    <span class="label-text">{t!(i18n, purge_confirm)}</span>
+                  </label>
+                  <button type="submit" class="btn btn-sm btn-error">
+                    {t!(i18n, purge_user)}
+                  </button>
+                </ActionForm>
+              </div>
+            }
+        })}
+  }
+}
@@ -0,0 +1,366 @@
+use crate::{
+  errors::LemmyAppError,
+  i18n::*,
+  lemmy_client::*,
+  ui::components::{
+    comment::comment_nodes::CommentNodes,
+    common::sort::{sort_type_label, SORT_TYPES},
+    person::person_actions::PersonActions,
+    post::post_listings::PostListings,
+  },
+};
+use lemmy_api_common::{
+  lemmy_db_schema::SortType,
+  lemmy_db_views_actor::structs::{CommunityModeratorView, PersonView},
+  person::GetPersonDetails,
+  site::GetSiteResponse,
+};
+use leptos::*;
+use leptos_router::*;
+
+const PAGE_LIMIT: i64 = 20;
+
+/// The tabs of a profile, kept in the `view` query parameter.
+#[derive(Clone, Copy, PartialEq)]
+enum ProfileView {
+  Overview,
+  Posts,
+  Comments,
+  Saved,
+}
+
+impl ProfileView {
+  fn from_query(view: Option<&String>) -> Self {
+    match view.map(String::as_str) {
+      Some("posts") => Self::Posts,
+      Some("comments") => Self::Comments,
+      Some("saved") => Self::Saved,
+      _ => Self::Overview,
+    }
+  }
+
+  fn as_query(self) -> &'static str {
+    match self {
+      Self::Overview => "overview",
+      Self::Posts => "posts",
+      Self::Comments => "comments",
+      Self::Saved => "saved",
+    }
+  }
+}
+
+#[component]
+pub fn ProfileActivity(
+  site_signal: RwSignal<Option<Result<GetSiteResponse, LemmyAppError>>>,
+) -> impl IntoView {
+  let i18n = use_i18n();
+
+  let params = use_params_map();
+  let query = use_query_map();
+  let user = expect_context::<RwSignal<Option<bool>>>();
+  let client = store_value(use_lemmy_client());
+
+  let name = move || params.get().get("name").cloned().unwrap_or_default();
+
+  let view_func = move || ProfileView::from_query(query.get().get("view"));
+
+  let sort_func = move || {
+    query
+      .get()
+      .get("sort")
+      .and_then(|s| serde_json::from_str::<SortType>(s).ok())
+      .unwrap_or(SortType::New)
+  };
+
+  let page_func = move || {
+    query
+      .get()
+      .get("page")
+      .and_then(|p| p.parse::<i64>().ok())
+      .filter(|p| *p > 0)
+      .unwrap_or(1)
+  };
+
+  // only the person themselves gets to see what they saved
+  let is_me = move || {
+    site_signal
+      .get()
+      .and_then(Result::ok)
+      .and_then(|s| s.my_user)
+      .is_some_and(|m| m.local_user_view.person.name == name())
+  };
+
+  let details = create_resource(
+    move || (user.get(), name(), view_func(), sort_func(), page_func()),
+    move |(_user, name, view, sort, page)| async move {
+      let form = GetPersonDetails {
+        username: Some(name),
+        sort: Some(sort),
+        page: Some(page),
+        limit: Some(PAGE_LIMIT),
+        saved_only: Some(view == ProfileView::Saved),
+        ..Default::default()
+      };
+      client.get_value().get_person_details(form).await
+    },
+  );
+
+  let tab_href = move |view: ProfileView| {
+    let mut query_params = query.get();
+    query_params.insert("view".into(), view.as_query().into());
+    query_params.remove("page");
+    query_params.to_query_string()
+  };
+
+  let tab_class = move |view: ProfileView| {
+    format!(
+      "tab {}",
+      if view == view_func() {
+        "tab-active"
+      } else {
+        ""
+      }
+    )
+  };
+
+  let page_href = move |page: i64| {
+    let mut query_params = query.get();
+    query_params.insert("page".into(), page.to_string());
+    query_params.to_query_string()
+  };
+
+  view! {
+    <main class="mx-auto">
+      <Transition fallback=|| {}>
+        {move || {
+            // a person that fails to load fails the whole page, see `routed_error_page`
+            details
+                .get()
+                .map(|res| {
+                    res.map(|res| {
+                        view! {
+                          <ProfileHeader
+                            person_view=res.person_view
+                            moderates=res.moderates
+                            site_signal
+                          />
+                        }
+                    })
+                })
+        }}
+
+      </Transition>
+      <div class="flex flex-wrap items-center gap-3 px-6 mb-3">
+        <div role="tablist" class="tabs tabs-boxed">
+          <A
+            role="tab"
+            href=move || tab_href(ProfileView::Overview)
+            class=move || tab_class(ProfileView::Overview)
+          >
+            {t!(i18n, overview)}
+          </A>
+          <A
+            role="tab"
+            href=move || tab_href(ProfileView::Posts)
+            class=move || tab_class(ProfileView::Posts)
+          >
+            {t!(i18n, posts)}
+          </A>
+          <A
+            role="tab"
+            href=move || tab_href(ProfileView::Comments)
+            class=move || tab_class(ProfileView::Comments)
+          >
+            {t!(i18n, comments)}
+          </A>
+          <Show when=is_me>
+            <A
+              role="tab"
+              href=move || tab_href(ProfileView::Saved)
+              class=move || tab_class(ProfileView::Saved)
+            >
+              {t!(i18n, saved)}
+            </A>
+          </Show>
+        </div>
+        <Form
+          method="GET"
+          action=move || format!("/u/{}", name())
+          class="flex flex-wrap items-center gap-3"
+        >
+          <input type="hidden" name="view" value=move || view_func().as_query()/>
+          <select class="select select-bordered" name="sort">
+            {SORT_TYPES
+                .into_iter()
+                .map(|s| {
+                    view! {
+                      <option
+                        value=serde_json::to_string(&s).unwrap_or_default()
+                        selected=move || s == sort_func()
+                      >
+                        {move || sort_type_label(s)}
+                      </option>
+                    }
+                })
+                .collect_view()}
+          </select>
+          <button type="submit" class="btn">
+            {t!(i18n, sort)}
+          </button>
+        </Form>
+      </div>
+      <Transition fallback=|| {}>
+        {move || {
+            details
+                .get()
+                .and_then(Result::ok)
+                .map(|res| {
+                    let view = view_func();
+                    let page = page_func();
+                    let more = res.posts.len() as i64 >= PAGE_LIMIT
+                        || res.comments.len() as i64 >= PAGE_LIMIT;
+                    let show_posts = view != ProfileView::Comments;
+                    let show_comments = view != ProfileView::Posts;
+                    view! {
+                      {show_posts
+                          .then(|| {
+                              view! {
+                                <div class="columns-1 2xl:columns-2 4xl:columns-3 gap-3">
+                                  <PostListings posts=res.posts.into()/>
+                                </div>
+                              }
+                          })}
+                      {show_comments
+                          .then(|| {
+                              view! {
+                                <div class="px-6">
+                                  <CommentNodes comments=res.comments.into()/>
+                                </div>
+                              }
+                          })}
+                      <div class="px-6">
+                        {(page > 1)
+                            .then(|| {
+                                view! {
+                                  <A href=page_href(page - 1) class="btn">
+                                    "Prev"
+                                  </A>
+                                }
+                            })}
+                        " "
+                        {more
+                            .then(|| {
+                                view! {
+                                  <A href=page_href(page + 1) class="btn">
+                                    "Next"
+                                  </A>
+                                }
+                            })}
+
+                      </div>
+                    }
+                })
+        }}
+
+      </Transition>
+    </main>
+  }
+}
+
+#[component]
+fn ProfileHeader(
+  person_view: PersonView,
+  moderates: Vec<CommunityModeratorView>,
+  site_signal: RwSignal<Option<Result<GetSiteResponse, LemmyAppError>>>,
+) -> impl IntoView {
+  let i18n = use_i18n();
+
+  let person = person_view.person.clone();
+  let (post_count, comment_count) = (
+    person_view.counts.post_count,
+    person_view.counts.comment_count,
+  );
+
+  view! {
+    <div class="card w-full bg-base-300 text-base-content mb-3">
+      {person
+          .banner
+          .clone()
+          .map(|b| {
+              view! {
+                <figure>
+                  <img class="w-full max-h-48 object-cover" src=b.inner().to_string()/>
+                </figure>
+              }
+          })}
+      <div class="card-body flex-row flex-wrap items-start gap-4">
+        {person
+            .avatar
+            .clone()
+            .map(|a| {
+                view! { <img class="w-24 h-24 rounded-full" src=a.inner().to_string()/> }
+            })}
+        <div class="flex-grow">
+          <h1 class="card-title text-2xl">
+            {person.display_name.clone().unwrap_or(person.name.clone())}
+          </h1>
+          <span class="text-sm">"@" {person.name.clone()}</span>
+          {person
+              .banned
+              .then(|| view! { <span class="badge badge-error ml-2">{t!(i18n, banned)}</span> })}
+          {person
+              .bot_account
+              .then(|| {
+                  view! { <span class="badge badge-info ml-2">{t!(i18n, bot_account)}</span> }
+              })}
+          <p>
+            <span class="badge badge-neutral inline-block whitespace-nowrap">
+              {post_count}
+              " "
+              {t!(i18n, posts)}
+            </span>
+            " "
+            <span class="badge badge-neutral inline-block whitespace-nowrap">
+              {comment_count}
+              " "
+              {t!(i18n, comments)}
+            </span>
+            " "
+            // the cake day is the day the account was made
+            <span class="badge badge-neutral inline-block whitespace-nowrap">
+              "🎂 "
+              {t!(i18n, joined)}
+              " "
+              {person.published.format("%Y-%m-%d").to_string()}
+            </span>
+          </p>
+          {person.bio.clone().map(|b| view! { <p class="whitespace-pre-wrap mt-2">{b}</p> })}
+          {(!moderates.is_empty())
+              .then(|| {
+                  view! {
+                    <h3 class="font-bold mt-2">{t!(i18n, moderates)}</h3>
+                    <p>
+                      {moderates
+                          .into_iter()
+                          .map(|m| {
+                              view! {
+                                <A
+                                  href=format!("/c/{}", m.community.name)
+                                  class="badge badge-neutral inline-block whitespace-nowrap"
+                                >
+                                  {m.community.title}
+                                </A>
+                                " "
+                              }
+                          })
+                          .collect_view()}
+                    </p>
+                  }
+              })}
+
+        </div>
+        <PersonActions person_view site_signal/>
+      </div>
+    </div>
+  }
+}
@@ -1,3 +1,4 @@
 pub mod post_activity;
+pub mod post_feed;
 pub mod post_listing;
 pub mod post_listings;
@@ -1,4 +1,5 @@
 use crate::{
+  errors::message_from_error,
   lemmy_client::*,
   ui::components::{comment::comment_nodes::CommentNodes, post::post_listing::PostListing},
 };
@@ -9,6 +10,7 @@ use leptos_router::use_params_map;
 #[component]
 pub fn PostActivity() -> impl IntoView {
   let params = use_params_map();
+  let client = store_value(use_lemmy_client());
 
   let post_id = move || params.get().get("id").cloned().unwrap_or_default();
 
@@ -18,7 +20,7 @@ pub fn PostActivity() -> impl IntoView {
       id: Some(PostId(id)),
       comment_id: None,
     };
-    LemmyClient.get_post(form).await
+    client.get_value().get_post(form).await
   });
 
   let comments = create_resource(post_id, move |id_string| async move {
@@ -37,7 +39,7 @@ pub fn PostActivity() -> impl IntoView {
       disliked_only: None,
       liked_only: None,
     };
-    LemmyClient.get_comments(form).await
+    client.get_value().get_comments(form).await
   });
 
   view! {
@@ -47,18 +49,16 @@ pub fn PostActivity() -> impl IntoView {
           view! { "Loading..." }
       }>
         {move || {
+            // a post that fails to load fails the whole page, see `routed_error_page`
             post.get()
-                .map(|res| match res {
-                    Err(e) => {
-                        view! { <div>{e.to_string()}</div> }
-                    }
-                    Ok(res) => {
+                .map(|res| {
+                    res.map(|res| {
                         view! {
                           <div>
                             <PostListing post_view=res.post_view.into()/>
                           </div>
                         }
-                    }
+                    })
                 })
         }}
 
@@ -71,7 +71,7 @@ pub fn PostActivity() -> impl IntoView {
                 .get()
                 .map(|res| match res {
                     Err(e) => {
-                        view! { <div>{e.to_string()}</div> }
+                        view! { <div class="alert alert-error">{message_from_error(&e)}</div> }
                     }
                     Ok(res) => {
                         view! {
@@ -1,80 +1,79 @@
 use crate::{
   errors::{LemmyAppError, LemmyAppErrorType},
+  flash::use_flash,
+  i18n::*,
   lemmy_client::*,
+  notifications::use_notifications,
+  redirect::use_return_to,
   ui::components::common::icon::{
     Icon,
     IconType::{Block, Comments, Crosspost, Downvote, Report, Save, Upvote, VerticalDots},
   },
 };
+#[cfg(feature = "ssr")]
+use crate::{flash::finish_form, redirect::return_path};
 use lemmy_api_common::{lemmy_db_views::structs::*, person::*, post::*};
 use leptos::*;
 use leptos_router::*;
 use web_sys::SubmitEvent;
 
 #[server(VotePostFn, "/serverfn")]
-pub async fn vote_post_fn(post_id: i32, score: i16) -> Result<Option<PostResponse>, ServerFnError> {
+pub async fn vote_post_fn(
+  post_id: i32,
+  score: i16,
+  return_to: Option<String>,
+) -> Result<PostResponse, ServerFnError<LemmyAppError>> {
   use lemmy_api_common::lemmy_db_schema::newtypes::PostId;
 
   let form = CreatePostLike {
     post_id: PostId(post_id),
     score,
   };
-  let result = LemmyClient.like_post(form).await;
+  let result = use_lemmy_client().like_post(form).await;
 
-  use leptos_actix::redirect;
+  // back to where the post was, rather than the front page
+  let back = return_path(return_to, Some(format!("post-{post_id}"))).await;
 
-  match result {
-    Ok(o) => Ok(Some(o)),
-    Err(e) => {
-      redirect(&format!("/?error={}", serde_json::to_string(&e)?)[..]);
-      Ok(None)
-    }
-  }
+  finish_form(result, &back, None).await
 }
 
 #[server(SavePostFn, "/serverfn")]
-pub async fn save_post_fn(post_id: i32, save: bool) -> Result<Option<PostResponse>, ServerFnError> {
+pub async fn save_post_fn(
+  post_id: i32,
+  save: bool,
+  return_to: Option<String>,
+) -> Result<PostResponse, ServerFnError<LemmyAppError>> {
   use lemmy_api_common::lemmy_db_schema::newtypes::PostId;
 
   let form = SavePost {
     post_id: PostId(post_id),
     save,
   };
-  let result = LemmyClient.save_post(form).await;
+  let result = use_lemmy_client().save_post(form).await;
 
-  use leptos_actix::redirect;
+  let back = return_path(return_to, Some(format!("post-{post_id}"))).await;
 
-  match result {
-    Ok(o) => Ok(Some(o)),
-    Err(e) => {
-      redirect(&format!("/?error={}", serde_json::to_string(&e)?)[..]);
-      Ok(None)
-    }
-  }
+  finish_form(result, &back, None).await
 }
 
 #[server(BlockUserFn, "/serverfn")]
 pub async fn block_user_fn(
   person_id: i32,
   block: bool,
-) -> Result<Option<BlockPersonResponse>, ServerFnError> {
+  post_id: Option<i32>,
+  return_to: Option<String>,
+) -> Result<BlockPersonResponse, ServerFnError<LemmyAppError>> {
   use lemmy_api_common::lemmy_db_schema::newtypes::PersonId;
 
   let form = BlockPerson {
     person_id: PersonId(person_id),
     block,
   };
-  let result = LemmyClient.block_user(form).await;
+  let result = use_lemmy_client().block_user(form).await;
 
-  use leptos_actix::redirect;
+  let back = return_path(return_to, post_id.map(|id| format!("post-{id}"))).await;
 
-  match result {
-    Ok(o) => Ok(Some(o)),
-    Err(e) => {
-      redirect(&format!("/?error={}", serde_json::to_string(&e)?)[..]);
-      Ok(None)
-    }
-  }
+  finish_form(result, &back, None).await
 }
 
 fn validate_report(form: &CreatePostReport) -> Option<LemmyAppErrorType> {
@@ -84,12 +83,15 @@ fn validate_report(form: &CreatePostReport) -> Option<LemmyAppErrorType> {
   None
 }
 
-async fn try_report(form: CreatePostReport) -> Result<PostReportResponse, LemmyAppError> {
+async fn try_report(
+  client: LemmyClient,
+  form: CreatePostReport,
+) -> Result<PostReportResponse, LemmyAppError> {
   let val = validate_report(&form);
 
   match val {
     None => {
-      let result = LemmyClient.report_post(form).await;
+      let result = client.report_post(form).await;
 
       match result {
         Ok(o) => Ok(o),
@@ -107,32 +109,32 @@ async fn try_report(form: CreatePostReport) -> Result<PostReportResponse, LemmyA
 pub async fn report_post_fn(
   post_id: i32,
   reason: String,
-) -> Result<Option<PostReportResponse>, ServerFnError> {
+  return_to: Option<String>,
+) -> Result<PostReportResponse, ServerFnError<LemmyAppError>> {
   use lemmy_api_common::lemmy_db_schema::newtypes::PostId;
 
   let form = CreatePostReport {
     post_id: PostId(post_id),
     reason,
   };
-  let result = try_report(form).await;
+  let result = try_report(use_lemmy_client(), form).await;
 
-  use leptos_actix::redirect;
+  let back = return_path(return_to, Some(format!("post-{post_id}"))).await;
 
-  match result {
-    Ok(o) => Ok(Some(o)),
-    Err(e) => {
-      redirect(&format!("/?error={}", serde_json::to_string(&e)?)[..]);
-      Ok(None)
-    }
-  }
+  finish_form(result, &back, None).await
 }
 
 #[component]
 pub fn PostListing(post_view: MaybeSignal<PostView>) -> impl IntoView {
-  let error = expect_context::<RwSignal<Option<LemmyAppError>>>();
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let client = store_value(use_lemmy_client());
 
   let post_view = create_rw_signal(post_view.get());
 
+  // sent along by every form, so that without javascript the browser comes back to this page
+  let return_to = use_return_to();
+
   let vote_action = create_server_action::<VotePostFn>();
 
   let on_vote_submit = move |ev: SubmitEvent, score: i16| {
@@ -146,14 +148,14 @@ pub fn PostListing(post_view: MaybeSignal<PostView>) -> impl IntoView {
           score,
         };
 
-        let result = LemmyClient.like_post(form).await;
+        let result = client.get_value().like_post(form).await;
 
         match result {
           Ok(o) => {
             post_view.set(o.post_view);
           }
           Err(e) => {
-            error.set(Some(e));
+            notifications.error(e);
           }
         }
       },
@@ -191,14 +193,14 @@ pub fn PostListing(post_view: MaybeSignal<PostView>) -> impl IntoView {
           save: !post_view.get().saved,
         };
 
-        let result = LemmyClient.save_post(form).await;
+        let result = client.get_value().save_post(form).await;
 
         match result {
           Ok(o) => {
             post_view.set(o.post_view);
           }
           Err(e) => {
-            error.set(Some(e));
+            notifications.error(e);
           }
         }
       },
@@ -218,12 +220,14 @@ pub fn PostListing(post_view: MaybeSignal<PostView>) -> impl IntoView {
           block: true,
         };
 
-        let result = LemmyClient.block_user(form).await;
+        let result = client.get_value().block_user(form).await;
 
         match result {
-          Ok(_o) => {}
+          Ok(_o) => {
+            notifications.success(t!(i18n, user_blocked)().to_string());
+          }
           Err(e) => {
-            error.set(Some(e));
+            notifications.error(e);
           }
         }
       },
@@ -233,30 +237,14 @@ pub fn PostListing(post_view: MaybeSignal<PostView>) -> impl IntoView {
   let report_post_action = create_server_action::<ReportPostFn>();
   let report_validation = create_rw_signal::<String>("".into());
 
-  let query = use_query_map();
-  let ssr_error = move || query.with(|params| params.get("error").cloned());
-
-  if let Some(e) = ssr_error() {
-    let le = serde_json::from_str::<LemmyAppError>(&e[..]);
-
-    match le {
-      Ok(e) => match e {
-        LemmyAppError {
-          error_type: LemmyAppErrorType::MissingReason,
-          content: c,
-        } => {
-          let id = format!("{}", post_view.get().post.id);
-          if c.eq(&id) {
-            report_validation.set("input-error".to_string());
-          }
-        }
-        _ => {
-          report_validation.set("".to_string());
-        }
-      },
-      Err(_) => {
-        logging::error!("error decoding error - log and ignore in UI?");
-      }
+  // the reason was left out when reporting this post without javascript
+  if let Some(LemmyAppError {
+    error_type: LemmyAppErrorType::MissingReason,
+    content,
+  }) = use_flash().error()
+  {
+    if content == format!("{}", post_view.get().post.id) {
+      report_validation.set("input-error".to_string());
     }
   }
 
@@ -273,12 +261,15 @@ pub fn PostListing(post_view: MaybeSignal<PostView>) -> impl IntoView {
           reason: reason.get(),
         };
 
-        let result = try_report(form).await;
+        let result = try_report(client.get_value(), form).await;
 
         match result {
-          Ok(_o) => {}
+          Ok(_o) => {
+            report_validation.set("".to_string());
+            notifications.success(t!(i18n, post_reported)().to_string());
+          }
           Err(e) => {
-            error.set(Some(e.clone()));
+            notifications.error(e.clone());
 
             let _id = format!("{}", post_view.get().post.id);
 
@@ -300,10 +291,11 @@ pub fn PostListing(post_view: MaybeSignal<PostView>) -> impl IntoView {
   };
 
   view! {
-    <tr class="flex sm:table-row">
+    <tr class="flex sm:table-row" id=move || format!("post-{}", post_view.get().post.id)>
       <td class="flex flex-col items-center text-center w-16 hidden sm:table-cell">
         <ActionForm action=vote_action on:submit=on_up_vote_submit>
           <input type="hidden" name="post_id" value=format!("{}", post_view.get().post.id)/>
+          <input type="hidden" name="return_to" value=return_to/>
           <input
             type="hidden"
             name="score"
@@ -326,6 +318,7 @@ pub fn PostListing(post_view: MaybeSignal<PostView>) -> impl IntoView {
         <span class="block text-sm">{move || post_view.get().counts.score}</span>
         <ActionForm action=vote_action on:submit=on_down_vote_submit>
           <input type="hidden" name="post_id" value=format!("{}", post_view.get().post.id)/>
+          <input type="hidden" name="return_to" value=return_to/>
           <input
             type="hidden"
             name="score"
@@ -399,6 +392,7 @@ pub fn PostListing(post_view: MaybeSignal<PostView>) -> impl IntoView {
             class="flex items-center sm:hidden"
           >
             <input type="hidden" name="post_id" value=format!("{}", post_view.get().post.id)/>
+            <input type="hidden" name="return_to" value=return_to/>
             <input
               type="hidden"
               name="score"
@@ -419,6 +413,7 @@ pub fn PostListing(post_view: MaybeSignal<PostView>) -> impl IntoView {
             class="flex items-center sm:hidden"
           >
             <input type="hidden" name="post_id" value=format!("{}", post_view.get().post.id)/>
+            <input type="hidden" name="return_to" value=return_to/>
             <input
               type="hidden"
               name="score"
@@ -450,6 +445,7 @@ pub fn PostListing(post_view: MaybeSignal<PostView>) -> impl IntoView {
           </span>
           <ActionForm action=save_post_action on:submit=on_save_submit class="flex items-center">
             <input type="hidden" name="post_id" value=format!("{}", post_view.get().post.id)/>
+            <input type="hidden" name="return_to" value=return_to/>
             <input type="hidden" name="save" value=move || format!("{}", !post_view.get().saved)/>
             <button
               type="submit"
@@ -472,6 +468,7 @@ pub fn PostListing(post_view: MaybeSignal<PostView>) -> impl IntoView {
               <li>
                 <ActionForm action=report_post_action on:submit=on_report_submit>
                   <input type="hidden" name="post_id" value=format!("{}", post_view.get().post.id)/>
+                  <input type="hidden" name="return_to" value=return_to/>
                   <input
                     class=move || format!("input input-bordered {}", report_validation.get())
                     type="text"
@@ -493,6 +490,8 @@ pub fn PostListing(post_view: MaybeSignal<PostView>) -> impl IntoView {
                     value=format!("{}", post_view.get().creator.id.0)
                   />
                   <input type="hidden" name="block" value="true"/>
+                  <input type="hidden" name="post_id" value=format!("{}", post_view.get().post.id)/>
+                  <input type="hidden" name="return_to" value=return_to/>
                   <button class="text-xs whitespace-nowrap" title="Block user" type="submit">
                     <Icon icon=Block class="inline-block".into()/>
                     " Block user"
@@ -0,0 +1,167 @@
+use crate::{
+  errors::{LemmyAppError, LemmyAppResult},
+  i18n::*,
+  lemmy_client::*,
+  ui::components::private_message::{
+    conversations_activity::{load_message_pages, message_pages, older_messages_href},
+    message_form::MessageForm,
+    private_message_item::PrivateMessageItem,
+  },
+};
+use lemmy_api_common::{
+  lemmy_db_schema::newtypes::PersonId,
+  lemmy_db_views::structs::PrivateMessageView,
+  lemmy_db_views_actor::structs::PersonView,
+  person::GetPersonDetails,
+  private_message::GetPrivateMessages,
+  site::GetSiteResponse,
+};
+use leptos::*;
+use leptos_router::*;
+
+async fn load_conversation(
+  client: LemmyClient,
+  person_id: PersonId,
+  my_person_id: Option<PersonId>,
+  pages: i64,
+) -> LemmyAppResult<(PersonView, Vec<PrivateMessageView>, bool)> {
+  let form = GetPersonDetails {
+    person_id: Some(person_id),
+    limit: Some(1),
+    ..Default::default()
+  };
+  let person_view = client.get_person_details(form).await?.person_view;
+
+  // lemmy has no thread of its own and only filters by who wrote a message, so the conversation is
+  // what they wrote together with what the logged in user wrote to them
+  let mut creators = vec![Some(person_id)];
+  if my_person_id != Some(person_id) {
+    creators.push(my_person_id);
+  }
+
+  let mut messages = vec![];
+  let mut more = false;
+  let mut cutoff = None;
+  for creator_id in creators {
+    let form = GetPrivateMessages {
+      unread_only: Some(false),
+      creator_id,
+      ..Default::default()
+    };
+    let (written, written_more) = load_message_pages(&client, form, pages).await?;
+    // where one side has older messages left, older ones of the other side would show without what
+    // they answer, so both stop at the same point
+    if written_more {
+      cutoff = cutoff.max(written.last().map(|m| m.private_message.published));
+    }
+    more |= written_more;
+    messages.extend(
+      written
+        .into_iter()
+        .filter(|m| m.creator.id == person_id || m.recipient.id == person_id),
+    );
+  }
+  if let Some(cutoff) = cutoff {
+    messages.retain(|m| m.private_message.published >= cutoff);
+  }
+
+  // oldest first, like a chat
+  messages.sort_by_key(|m| m.private_message.published.timestamp_millis());
+
+  Ok((person_view, messages, more))
+}
+
+/// The messages between the logged in user and one other person, with a form to write another.
+#[component]
+pub fn ConversationActivity(
+  site_signal: RwSignal<Option<Result<GetSiteResponse, LemmyAppError>>>,
+) -> impl IntoView {
+  let i18n = use_i18n();
+
+  let params = use_params_map();
+  let query = use_query_map();
+  let user = expect_context::<RwSignal<Option<bool>>>();
+  let client = store_value(use_lemmy_client());
+
+  let person_id = move || {
+    PersonId(
+      params
+        .get()
+        .get("id")
+        .and_then(|id| id.parse::<i32>().ok())
+        .unwrap_or_default(),
+    )
+  };
+
+  let my_person_id = move || {
+    site_signal
+      .get()
+      .and_then(Result::ok)
+      .and_then(|s| s.my_user)
+      .map(|m| m.local_user_view.person.id)
+  };
+
+  let conversation = create_resource(
+    move || {
+      (
+        user.get(),
+        person_id(),
+        my_person_id(),
+        query.with(message_pages),
+      )
+    },
+    move |(_user, person_id, my_person_id, pages)| async move {
+      load_conversation(client.get_value(), person_id, my_person_id, pages).await
+    },
+  );
+
+  view! {
+    <main class="mx-auto">
+      <Transition fallback=|| {}>
+        {move || {
+            // an unknown person, or nobody logged in, fails the whole page, see `routed_error_page`
+            conversation
+                .get()
+                .map(|res| {
+                    res.map(|(person_view, messages, more)| {
+                        let my_person_id = my_person_id();
+                        let name = person_view.person.name;
+                        view! {
+                          <h2 class="p-6 text-4xl">
+                            {t!(i18n, conversation_with)} " "
+                            <A href=format!("/u/{name}") class="link">
+                              {name.clone()}
+                            </A>
+                          </h2>
+                          <div class="px-6">
+                            {more
+                                .then(|| {
+                                    view! {
+                                      <A href=query.with(older_messages_href) class="btn mb-3">
+                                        {t!(i18n, load_older)}
+                                      </A>
+                                    }
+                                })}
+                            {messages
+                                .is_empty()
+                                .then(|| view! { <p>{t!(i18n, no_messages)}</p> })}
+                            {messages
+                                .into_iter()
+                                .map(|message| view! { <PrivateMessageItem message my_person_id/> })
+                                .collect_view()}
+                          </div>
+                          <div class="p-6">
+                            <MessageForm
+                              recipient_id=person_view.person.id
+                              on_sent=move |()| conversation.refetch()
+                            />
+                          </div>
+                        }
+                    })
+                })
+        }}
+
+      </Transition>
+    </main>
+  }
+}
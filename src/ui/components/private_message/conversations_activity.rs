@@ -0,0 +1,211 @@
+use crate::{
+  errors::{LemmyAppError, LemmyAppResult},
+  i18n::*,
+  lemmy_client::*,
+};
+use lemmy_api_common::{
+  lemmy_db_schema::{newtypes::PersonId, source::person::Person},
+  lemmy_db_views::structs::PrivateMessageView,
+  private_message::GetPrivateMessages,
+  site::GetSiteResponse,
+};
+use leptos::*;
+use leptos_router::*;
+
+const MESSAGE_LIMIT: i64 = 50;
+
+/// The first `pages` pages of the private messages `form` asks for, newest first, and whether
+/// lemmy has older ones.
+pub async fn load_message_pages(
+  client: &LemmyClient,
+  form: GetPrivateMessages,
+  pages: i64,
+) -> LemmyAppResult<(Vec<PrivateMessageView>, bool)> {
+  let mut messages = vec![];
+  let mut more = false;
+
+  for page in 1..=pages {
+    let form = GetPrivateMessages {
+      page: Some(page),
+      limit: Some(MESSAGE_LIMIT),
+      ..form.clone()
+    };
+    let page_messages = client.get_private_messages(form).await?.private_messages;
+    // a page that comes back full probably has another one after it
+    more = page_messages.len() as i64 >= MESSAGE_LIMIT;
+    messages.extend(page_messages);
+    if !more {
+      break;
+    }
+  }
+
+  Ok((messages, more))
+}
+
+/// How many pages of messages to show, from the `pages` query parameter that "load older" links
+/// raise, so that it works without javascript too.
+pub fn message_pages(query: &ParamsMap) -> i64 {
+  query
+    .get("pages")
+    .and_then(|p| p.parse::<i64>().ok())
+    .filter(|p| *p > 0)
+    .unwrap_or(1)
+}
+
+/// The current page with one more page of messages.
+pub fn older_messages_href(query: &ParamsMap) -> String {
+  let mut query = query.clone();
+  query.insert("pages".into(), (message_pages(&query) + 1).to_string());
+  query.to_query_string()
+}
+
+/// Everything said between the logged in user and one other person.
+#[derive(Clone)]
+struct Conversation {
+  counterpart: Person,
+  latest: PrivateMessageView,
+  unread: usize,
+}
+
+// newest first, so the first message of each counterpart is the latest one
+fn group_conversations(
+  messages: Vec<PrivateMessageView>,
+  my_person_id: Option<PersonId>,
+) -> Vec<Conversation> {
+  let mut conversations: Vec<Conversation> = vec![];
+
+  for message in messages {
+    let received = Some(message.recipient.id) == my_person_id;
+    let counterpart = if received {
+      message.creator.clone()
+    } else {
+      message.recipient.clone()
+    };
+    let unread = usize::from(received && !message.private_message.read);
+
+    match conversations
+      .iter_mut()
+      .find(|c| c.counterpart.id == counterpart.id)
+    {
+      Some(c) => c.unread += unread,
+      None => conversations.push(Conversation {
+        counterpart,
+        latest: message,
+        unread,
+      }),
+    }
+  }
+
+  conversations
+}
+
+/// The people the logged in user exchanged messages with, latest conversation first.
+#[component]
+pub fn ConversationsActivity(
+  site_signal: RwSignal<Option<Result<GetSiteResponse, LemmyAppError>>>,
+) -> impl IntoView {
+  let i18n = use_i18n();
+
+  let query = use_query_map();
+  let user = expect_context::<RwSignal<Option<bool>>>();
+  let client = store_value(use_lemmy_client());
+
+  let my_person_id = move || {
+    site_signal
+      .get()
+      .and_then(Result::ok)
+      .and_then(|s| s.my_user)
+      .map(|m| m.local_user_view.person.id)
+  };
+
+  let conversations = create_resource(
+    move || (user.get(), my_person_id(), query.with(message_pages)),
+    move |(_user, my_person_id, pages)| async move {
+      let form = GetPrivateMessages {
+        unread_only: Some(false),
+        ..Default::default()
+      };
+      load_message_pages(&client.get_value(), form, pages)
+        .await
+        .map(|(messages, more)| (group_conversations(messages, my_person_id), more))
+    },
+  );
+
+  view! {
+    <main class="mx-auto">
+      <h2 class="p-6 text-4xl">{t!(i18n, conversations)}</h2>
+      <Transition fallback=|| {}>
+        {move || {
+            // not being logged in fails the whole page, see `routed_error_page`
+            conversations
+                .get()
+                .map(|res| {
+                    res.map(|(conversations, more)| {
+                        view! {
+                          {conversations
+                              .is_empty()
+                              .then(|| view! { <p class="px-6">{t!(i18n, no_messages)}</p> })}
+                          <ul class="px-6">
+                            {conversations
+                                .into_iter()
+                                .map(|c| view! { <ConversationRow conversation=c/> })
+                                .collect_view()}
+                          </ul>
+                          {more
+                              .then(|| {
+                                  view! {
+                                    <div class="px-6">
+                                      <A href=query.with(older_messages_href) class="btn">
+                                        {t!(i18n, load_older)}
+                                      </A>
+                                    </div>
+                                  }
+                              })}
+                        }
+                    })
+                })
+        }}
+
+      </Transition>
+    </main>
+  }
+}
+
+#[component]
+fn ConversationRow(conversation: Conversation) -> impl IntoView {
+  let i18n = use_i18n();
+
+  let Conversation {
+    counterpart,
+    latest,
+    unread,
+  } = conversation;
+
+  view! {
+    <li class="card bg-base-300 text-base-content mb-3">
+      <A href=format!("/messages/{}", counterpart.id.0) class="card-body">
+        <p class="text-sm">
+          <span class="font-bold">{counterpart.name}</span>
+          " · "
+          {latest.private_message.published.format("%Y-%m-%d %H:%M").to_string()}
+          " "
+          {(unread > 0)
+              .then(|| {
+                  view! {
+                    <span class="badge badge-primary">{unread} " " {t!(i18n, unread)}</span>
+                  }
+              })}
+
+        </p>
+        <p class="truncate">
+          {if latest.private_message.deleted {
+              t!(i18n, message_deleted)().to_string()
+          } else {
+              latest.private_message.content
+          }}
+
+        </p>
+      </A>
+    </li>
+  }
+}
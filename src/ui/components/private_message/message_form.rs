@@ -0,0 +1,127 @@
+use crate::{
+  errors::{LemmyAppError, LemmyAppErrorType},
+  flash::use_flash,
+  i18n::*,
+  lemmy_client::*,
+  notifications::use_notifications,
+  redirect::use_return_to,
+};
+#[cfg(feature = "ssr")]
+use crate::{flash::finish_form, redirect::return_path};
+use lemmy_api_common::{
+  lemmy_db_schema::newtypes::PersonId,
+  private_message::{CreatePrivateMessage, PrivateMessageResponse},
+};
+use leptos::*;
+use leptos_router::*;
+use web_sys::SubmitEvent;
+
+async fn try_send(
+  client: LemmyClient,
+  form: CreatePrivateMessage,
+) -> Result<PrivateMessageResponse, LemmyAppError> {
+  if form.content.trim().is_empty() {
+    return Err(LemmyAppError {
+      error_type: LemmyAppErrorType::EmptyContent,
+      content: format!("{}", form.recipient_id.0),
+    });
+  }
+
+  client.create_private_message(form).await
+}
+
+#[server(CreatePrivateMessageFn, "/serverfn")]
+pub async fn create_private_message_fn(
+  recipient_id: i32,
+  content: String,
+  return_to: Option<String>,
+) -> Result<PrivateMessageResponse, ServerFnError<LemmyAppError>> {
+  let form = CreatePrivateMessage {
+    content,
+    recipient_id: PersonId(recipient_id),
+  };
+  let result = try_send(use_lemmy_client(), form).await;
+
+  // sent, back to the new message, otherwise to the form
+  let ok_to = match &result {
+    Ok(o) => {
+      let anchor = format!("message-{}", o.private_message_view.private_message.id.0);
+      Some(return_path(return_to.clone(), Some(anchor)).await)
+    }
+    Err(_) => None,
+  };
+  let back = return_path(return_to, None).await;
+
+  finish_form(result, &back, ok_to.as_deref()).await
+}
+
+/// Writes a new private message to `recipient_id`. `on_sent` runs once lemmy has it.
+#[component]
+pub fn MessageForm(recipient_id: PersonId, #[prop(into)] on_sent: Callback<()>) -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let client = store_value(use_lemmy_client());
+  let return_to = use_return_to();
+
+  let send_action = create_server_action::<CreatePrivateMessageFn>();
+  let content = RwSignal::new(String::new());
+  let validation = create_rw_signal::<String>("".into());
+
+  // this message was left empty without javascript
+  if let Some(LemmyAppError {
+    error_type: LemmyAppErrorType::EmptyContent,
+    content: failed,
+  }) = use_flash().error()
+  {
+    if failed == format!("{}", recipient_id.0) {
+      validation.set("textarea-error".to_string());
+    }
+  }
+
+  let on_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let form = CreatePrivateMessage {
+          content: content.get_untracked(),
+          recipient_id,
+        };
+
+        let result = try_send(client.get_value(), form).await;
+
+        match result {
+          Ok(_o) => {
+            content.set(String::new());
+            validation.set("".to_string());
+            on_sent.call(());
+          }
+          Err(e) => {
+            if e.error_type == LemmyAppErrorType::EmptyContent {
+              validation.set("textarea-error".to_string());
+            }
+            notifications.error(e);
+          }
+        }
+      },
+    );
+  };
+
+  view! {
+    <ActionForm action=send_action on:submit=on_submit class="flex flex-col gap-2">
+      <input type="hidden" name="recipient_id" value=format!("{}", recipient_id.0)/>
+      <input type="hidden" name="return_to" value=return_to/>
+      <textarea
+        class=move || format!("textarea textarea-bordered {}", validation.get())
+        name="content"
+        placeholder=move || t!(i18n, write_message)().to_string()
+        prop:value=content
+        on:input=move |e| content.set(event_target_value(&e))
+      ></textarea>
+      <button type="submit" class="btn btn-primary self-start">
+        {t!(i18n, send_message)}
+      </button>
+    </ActionForm>
+  }
+}
@@ -0,0 +1,4 @@
+pub mod conversation_activity;
+pub mod conversations_activity;
+pub mod message_form;
+pub mod private_message_item;
@@ -0,0 +1,368 @@
+#[cfg(feature = "ssr")]
+use crate::{errors::LemmyAppResult, flash::finish_form, redirect::return_path};
+use crate::{
+  errors::{LemmyAppError, LemmyAppErrorType},
+  i18n::*,
+  lemmy_client::*,
+  notifications::use_notifications,
+  redirect::use_return_to,
+  ui::components::{
+    common::icon::{
+      Icon,
+      IconType::{Report, VerticalDots},
+    },
+    inbox::inbox_item::MarkMessageAsReadFn,
+  },
+};
+use lemmy_api_common::{
+  lemmy_db_schema::newtypes::PersonId,
+  lemmy_db_views::structs::PrivateMessageView,
+  private_message::{
+    CreatePrivateMessageReport,
+    DeletePrivateMessage,
+    EditPrivateMessage,
+    MarkPrivateMessageAsRead,
+    PrivateMessageReportResponse,
+    PrivateMessageResponse,
+  },
+};
+use leptos::*;
+use leptos_router::*;
+use web_sys::SubmitEvent;
+
+fn message_anchor(private_message_id: i32) -> Option<String> {
+  Some(format!("message-{private_message_id}"))
+}
+
+#[server(EditPrivateMessageFn, "/serverfn")]
+pub async fn edit_private_message_fn(
+  private_message_id: i32,
+  content: String,
+  return_to: Option<String>,
+) -> Result<PrivateMessageResponse, ServerFnError<LemmyAppError>> {
+  use lemmy_api_common::lemmy_db_schema::newtypes::PrivateMessageId;
+
+  let back = return_path(return_to, message_anchor(private_message_id)).await;
+
+  let result: LemmyAppResult<PrivateMessageResponse> = async {
+    if content.trim().is_empty() {
+      return Err(LemmyAppError {
+        error_type: LemmyAppErrorType::EmptyContent,
+        content: format!("{private_message_id}"),
+      });
+    }
+
+    let form = EditPrivateMessage {
+      private_message_id: PrivateMessageId(private_message_id),
+      content,
+    };
+    use_lemmy_client().edit_private_message(form).await
+  }
+  .await;
+
+  finish_form(result, &back, None).await
+}
+
+#[server(DeletePrivateMessageFn, "/serverfn")]
+pub async fn delete_private_message_fn(
+  private_message_id: i32,
+  deleted: bool,
+  return_to: Option<String>,
+) -> Result<PrivateMessageResponse, ServerFnError<LemmyAppError>> {
+  use lemmy_api_common::lemmy_db_schema::newtypes::PrivateMessageId;
+
+  let form = DeletePrivateMessage {
+    private_message_id: PrivateMessageId(private_message_id),
+    deleted,
+  };
+  let result = use_lemmy_client().delete_private_message(form).await;
+
+  let back = return_path(return_to, message_anchor(private_message_id)).await;
+
+  finish_form(result, &back, None).await
+}
+
+#[server(ReportPrivateMessageFn, "/serverfn")]
+pub async fn report_private_message_fn(
+  private_message_id: i32,
+  reason: String,
+  return_to: Option<String>,
+) -> Result<PrivateMessageReportResponse, ServerFnError<LemmyAppError>> {
+  use lemmy_api_common::lemmy_db_schema::newtypes::PrivateMessageId;
+
+  let back = return_path(return_to, message_anchor(private_message_id)).await;
+
+  let result: LemmyAppResult<PrivateMessageReportResponse> = async {
+    if reason.trim().is_empty() {
+      return Err(LemmyAppError {
+        error_type: LemmyAppErrorType::MissingReason,
+        content: format!("{private_message_id}"),
+      });
+    }
+
+    let form = CreatePrivateMessageReport {
+      private_message_id: PrivateMessageId(private_message_id),
+      reason,
+    };
+    use_lemmy_client().report_private_message(form).await
+  }
+  .await;
+
+  finish_form(result, &back, None).await
+}
+
+/// One message of a conversation. Whoever wrote it can edit and delete it, whoever got it can mark
+/// it read and report it.
+#[component]
+pub fn PrivateMessageItem(
+  message: PrivateMessageView,
+  my_person_id: Option<PersonId>,
+) -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let client = store_value(use_lemmy_client());
+  let return_to = use_return_to();
+
+  let private_message_id = message.private_message.id;
+  let mine = Some(message.creator.id) == my_person_id;
+  let message = create_rw_signal(message);
+
+  let set_message = move |result: Result<PrivateMessageResponse, LemmyAppError>| match result {
+    Ok(o) => {
+      message.set(o.private_message_view);
+    }
+    Err(e) => {
+      notifications.error(e);
+    }
+  };
+
+  let edit_action = create_server_action::<EditPrivateMessageFn>();
+  let edited = RwSignal::new(message.get_untracked().private_message.content);
+
+  let on_edit_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        if edited.get_untracked().trim().is_empty() {
+          notifications.error(LemmyAppErrorType::EmptyContent.into());
+          return;
+        }
+
+        let form = EditPrivateMessage {
+          private_message_id,
+          content: edited.get_untracked(),
+        };
+
+        set_message(client.get_value().edit_private_message(form).await);
+      },
+    );
+  };
+
+  let delete_action = create_server_action::<DeletePrivateMessageFn>();
+
+  let on_delete_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let form = DeletePrivateMessage {
+          private_message_id,
+          deleted: !message.get_untracked().private_message.deleted,
+        };
+
+        set_message(client.get_value().delete_private_message(form).await);
+      },
+    );
+  };
+
+  let mark_action = create_server_action::<MarkMessageAsReadFn>();
+
+  let on_mark_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let form = MarkPrivateMessageAsRead {
+          private_message_id,
+          read: !message.get_untracked().private_message.read,
+        };
+
+        set_message(client.get_value().mark_private_message_as_read(form).await);
+      },
+    );
+  };
+
+  let report_action = create_server_action::<ReportPrivateMessageFn>();
+  let reason = RwSignal::new(String::new());
+
+  let on_report_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        if reason.get_untracked().trim().is_empty() {
+          notifications.error(LemmyAppErrorType::MissingReason.into());
+          return;
+        }
+
+        let form = CreatePrivateMessageReport {
+          private_message_id,
+          reason: reason.get_untracked(),
+        };
+
+        let result = client.get_value().report_private_message(form).await;
+
+        match result {
+          Ok(_o) => {
+            reason.set(String::new());
+            notifications.success(t!(i18n, message_reported)().to_string());
+          }
+          Err(e) => {
+            notifications.error(e);
+          }
+        }
+      },
+    );
+  };
+
+  view! {
+    <div
+      id=format!("message-{}", private_message_id.0)
+      class=if mine { "chat chat-end" } else { "chat chat-start" }
+    >
+      <div class="chat-header text-sm">
+        {move || message.get().private_message.published.format("%Y-%m-%d %H:%M").to_string()}
+        " "
+        <Show when=move || !mine && !message.get().private_message.read>
+          <span class="badge badge-primary">{t!(i18n, unread)}</span>
+        </Show>
+      </div>
+      <div class=if mine {
+          "chat-bubble chat-bubble-primary whitespace-pre-wrap"
+      } else {
+          "chat-bubble whitespace-pre-wrap"
+      }>
+        {move || {
+            if message.get().private_message.deleted {
+                t!(i18n, message_deleted)().to_string()
+            } else {
+                message.get().private_message.content
+            }
+        }}
+
+      </div>
+      <div class="chat-footer flex gap-2 items-start">
+        {if mine {
+            view! {
+              <details>
+                <summary class="btn btn-xs">{t!(i18n, edit)}</summary>
+                <ActionForm action=edit_action on:submit=on_edit_submit class="flex flex-col gap-2">
+                  <input
+                    type="hidden"
+                    name="private_message_id"
+                    value=format!("{}", private_message_id.0)
+                  />
+                  <input type="hidden" name="return_to" value=return_to/>
+                  <textarea
+                    class="textarea textarea-bordered"
+                    name="content"
+                    prop:value=edited
+                    on:input=move |e| edited.set(event_target_value(&e))
+                  >
+                    {edited.get_untracked()}
+                  </textarea>
+                  <button type="submit" class="btn btn-xs self-start">
+                    {t!(i18n, save)}
+                  </button>
+                </ActionForm>
+              </details>
+              <ActionForm action=delete_action on:submit=on_delete_submit>
+                <input
+                  type="hidden"
+                  name="private_message_id"
+                  value=format!("{}", private_message_id.0)
+                />
+                <input
+                  type="hidden"
+                  name="deleted"
+                  value=move || format!("{}", !message.get().private_message.deleted)
+                />
+                <input type="hidden" name="return_to" value=return_to/>
+                <button type="submit" class="btn btn-xs">
+                  {move || {
+                      if message.get().private_message.deleted {
+                          t!(i18n, restore)().to_string()
+                      } else {
+                          t!(i18n, delete)().to_string()
+                      }
+                  }}
+
+                </button>
+              </ActionForm>
+            }
+                .into_view()
+        } else {
+            view! {
+              <ActionForm action=mark_action on:submit=on_mark_submit>
+                <input
+                  type="hidden"
+                  name="private_message_id"
+                  value=format!("{}", private_message_id.0)
+                />
+                <input
+                  type="hidden"
+                  name="read"
+                  value=move || format!("{}", !message.get().private_message.read)
+                />
+                <input type="hidden" name="return_to" value=return_to/>
+                <button type="submit" class="btn btn-xs">
+                  {move || {
+                      if message.get().private_message.read {
+                          t!(i18n, mark_as_unread)().to_string()
+                      } else {
+                          t!(i18n, mark_as_read)().to_string()
+                      }
+                  }}
+
+                </button>
+              </ActionForm>
+              <div class="dropdown">
+                <label tabindex="0" class="btn btn-xs">
+                  <Icon icon=VerticalDots/>
+                </label>
+                <div tabindex="0" class="dropdown-content z-[1] bg-base-100 rounded-box shadow p-2">
+                  <ActionForm action=report_action on:submit=on_report_submit class="flex gap-2">
+                    <input
+                      type="hidden"
+                      name="private_message_id"
+                      value=format!("{}", private_message_id.0)
+                    />
+                    <input type="hidden" name="return_to" value=return_to/>
+                    <input
+                      class="input input-bordered input-sm"
+                      type="text"
+                      name="reason"
+                      required
+                      placeholder=move || t!(i18n, reason)().to_string()
+                      prop:value=reason
+                      on:input=move |e| reason.set(event_target_value(&e))
+                    />
+                    <button type="submit" class="btn btn-xs" title="Report message">
+                      <Icon icon=Report class="inline-block".into()/>
+                    </button>
+                  </ActionForm>
+                </div>
+              </div>
+            }
+                .into_view()
+        }}
+
+      </div>
+    </div>
+  }
+}
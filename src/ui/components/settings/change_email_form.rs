@@ -0,0 +1,113 @@
+use crate::{
+  errors::{LemmyAppError, LemmyAppErrorType},
+  flash::use_flash,
+  i18n::*,
+  notifications::use_notifications,
+  redirect::use_return_to,
+};
+#[cfg(feature = "ssr")]
+use crate::{flash::finish_form, lemmy_client::*, redirect::return_path};
+use lemmy_api_common::{site::GetSiteResponse, LemmyErrorType};
+use leptos::*;
+use leptos_router::*;
+
+fn is_about_email(error_type: &LemmyAppErrorType) -> bool {
+  matches!(
+    error_type,
+    LemmyAppErrorType::EmptyEmail
+      | LemmyAppErrorType::ApiError(LemmyErrorType::EmailAlreadyExists)
+      | LemmyAppErrorType::ApiError(LemmyErrorType::EmailRequired)
+  )
+}
+
+/// Changes the email address of the logged in user. Where the site verifies addresses, lemmy keeps
+/// the old one until the new one is verified through the link it emails.
+#[server(ChangeEmailFn, "/serverfn")]
+pub async fn change_email_fn(
+  email: String,
+  return_to: Option<String>,
+) -> Result<GetSiteResponse, ServerFnError<LemmyAppError>> {
+  use crate::errors::LemmyAppResult;
+  use lemmy_api_common::person::SaveUserSettings;
+
+  let result: LemmyAppResult<GetSiteResponse> = async {
+    if email.trim().is_empty() {
+      return Err(LemmyAppErrorType::EmptyEmail.into());
+    }
+
+    let form = SaveUserSettings {
+      email: Some(email.trim().to_string().into()),
+      ..Default::default()
+    };
+    let client = use_lemmy_client();
+    client.save_user_settings(form).await?;
+    // what lemmy made of it, for the hydrated app to show
+    client.get_site().await
+  }
+  .await;
+
+  let back = return_path(return_to, None).await;
+
+  finish_form(result, &back, None).await
+}
+
+#[component]
+pub fn ChangeEmailForm(
+  email: String,
+  site_signal: RwSignal<Option<Result<GetSiteResponse, LemmyAppError>>>,
+) -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let return_to = use_return_to();
+
+  let validation = create_rw_signal::<String>("".into());
+  if use_flash()
+    .error()
+    .is_some_and(|e| is_about_email(&e.error_type))
+  {
+    validation.set("input-error".to_string());
+  }
+
+  let change_action = create_server_action::<ChangeEmailFn>();
+
+  create_effect(move |_| match change_action.value().get() {
+    Some(Ok(site)) => {
+      // until the new address is verified, lemmy keeps the old one
+      if site.site_view.local_site.require_email_verification {
+        notifications.info(t!(i18n, check_email)().to_string());
+      } else {
+        notifications.success(t!(i18n, email_saved)().to_string());
+      }
+      // renders the settings anew with what lemmy saved
+      site_signal.set(Some(Ok(site)));
+    }
+    Some(Err(e)) => {
+      let e = LemmyAppError::from(e);
+      if is_about_email(&e.error_type) {
+        validation.set("input-error".to_string());
+      }
+      notifications.error(e);
+    }
+    None => {}
+  });
+
+  view! {
+    <ActionForm action=change_action class="px-6 flex flex-col gap-3 max-w-2xl">
+      <input type="hidden" name="return_to" value=return_to/>
+      <h3 class="text-2xl mb-3">{t!(i18n, change_email)}</h3>
+      <label class="form-control">
+        <span class="label-text">{t!(i18n, email)}</span>
+        <input
+          class=move || format!("input input-bordered {}", validation.get())
+          type="email"
+          name="email"
+          required
+          value=email
+        />
+      </label>
+      <button type="submit" class="btn btn-primary self-start">
+        {t!(i18n, change_email)}
+      </button>
+    </ActionForm>
+  }
+}
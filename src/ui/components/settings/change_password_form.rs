@@ -0,0 +1,148 @@
+#[cfg(feature = "ssr")]
+use crate::{
+  config::use_public_config,
+  cookie::set_auth_cookie,
+  flash::finish_form,
+  lemmy_client::*,
+  redirect::return_path,
+};
+use crate::{
+  errors::{LemmyAppError, LemmyAppErrorType},
+  flash::use_flash,
+  i18n::*,
+  notifications::use_notifications,
+  redirect::use_return_to,
+};
+use lemmy_api_common::LemmyErrorType;
+use leptos::*;
+use leptos_router::*;
+
+// the field an error is about
+fn invalid_field(error_type: &LemmyAppErrorType) -> Option<&'static str> {
+  match error_type {
+    LemmyAppErrorType::ApiError(LemmyErrorType::IncorrectLogin) => Some("old_password"),
+    LemmyAppErrorType::EmptyPassword
+    | LemmyAppErrorType::ApiError(LemmyErrorType::InvalidPassword) => Some("new_password"),
+    LemmyAppErrorType::PasswordMismatch
+    | LemmyAppErrorType::ApiError(LemmyErrorType::PasswordsDoNotMatch) => {
+      Some("new_password_verify")
+    }
+    _ => None,
+  }
+}
+
+/// Changes the password of the logged in user. Lemmy ends every session on the way, so the one it
+/// hands out in return replaces the session cookie.
+#[server(ChangePasswordFn, "/serverfn")]
+pub async fn change_password_fn(
+  old_password: String,
+  new_password: String,
+  new_password_verify: String,
+  return_to: Option<String>,
+) -> Result<(), ServerFnError<LemmyAppError>> {
+  use crate::errors::LemmyAppResult;
+  use lemmy_api_common::person::ChangePassword;
+
+  let result: LemmyAppResult<()> = async {
+    if old_password.is_empty() || new_password.is_empty() {
+      return Err(LemmyAppErrorType::EmptyPassword.into());
+    }
+    if new_password != new_password_verify {
+      return Err(LemmyAppErrorType::PasswordMismatch.into());
+    }
+
+    let form = ChangePassword {
+      new_password: new_password.into(),
+      new_password_verify: new_password_verify.into(),
+      old_password: old_password.into(),
+    };
+    let res = use_lemmy_client().change_password(form).await?;
+    if let Some(jwt) = res.jwt {
+      set_auth_cookie(&jwt.into_inner(), &use_public_config().cookie.max_age()).await?;
+    }
+    Ok(())
+  }
+  .await;
+
+  let back = return_path(return_to, None).await;
+
+  finish_form(result, &back, None).await
+}
+
+#[component]
+pub fn ChangePasswordForm() -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let return_to = use_return_to();
+
+  let old_password = create_rw_signal(String::new());
+  let new_password = create_rw_signal(String::new());
+  let new_password_verify = create_rw_signal(String::new());
+
+  let invalid = create_rw_signal::<Option<&'static str>>(None);
+  if let Some(e) = use_flash().error() {
+    invalid.set(invalid_field(&e.error_type));
+  }
+
+  // sent by the action itself, like the rest of the settings
+  let change_action = create_server_action::<ChangePasswordFn>();
+
+  create_effect(move |_| match change_action.value().get() {
+    Some(Ok(())) => {
+      invalid.set(None);
+      old_password.set(String::new());
+      new_password.set(String::new());
+      new_password_verify.set(String::new());
+      notifications.success(t!(i18n, password_changed)().to_string());
+    }
+    Some(Err(e)) => {
+      let e = LemmyAppError::from(e);
+      invalid.set(invalid_field(&e.error_type));
+      notifications.error(e);
+    }
+    None => {}
+  });
+
+  let password_input = move |name: &'static str, value: RwSignal<String>| {
+    view! {
+      <input
+        class=move || {
+            if invalid.get() == Some(name) {
+                "input input-bordered input-error"
+            } else {
+                "input input-bordered"
+            }
+        }
+
+        type="password"
+        name=name
+        required
+        autocomplete=if name == "old_password" { "current-password" } else { "new-password" }
+        prop:value=value
+        on:input=move |e| value.set(event_target_value(&e))
+      />
+    }
+  };
+
+  view! {
+    <ActionForm action=change_action class="px-6 flex flex-col gap-3 max-w-2xl">
+      <input type="hidden" name="return_to" value=return_to/>
+      <h3 class="text-2xl mb-3">{t!(i18n, change_password)}</h3>
+      <label class="form-control">
+        <span class="label-text">{t!(i18n, old_password)}</span>
+        {password_input("old_password", old_password)}
+      </label>
+      <label class="form-control">
+        <span class="label-text">{t!(i18n, new_password)}</span>
+        {password_input("new_password", new_password)}
+      </label>
+      <label class="form-control">
+        <span class="label-text">{t!(i18n, new_password_verify)}</span>
+        {password_input("new_password_verify", new_password_verify)}
+      </label>
+      <button type="submit" class="btn btn-primary self-start">
+        {t!(i18n, change_password)}
+      </button>
+    </ActionForm>
+  }
+}
@@ -0,0 +1,4 @@
+pub mod change_email_form;
+pub mod change_password_form;
+pub mod settings_activity;
+pub mod totp_form;
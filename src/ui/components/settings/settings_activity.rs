@@ -0,0 +1,417 @@
+#[cfg(feature = "ssr")]
+use crate::{
+  config::use_public_config,
+  cookie::{remove_cookie, set_cookie},
+  errors::LemmyAppResult,
+  flash::finish_form,
+  redirect::return_path,
+};
+use crate::{
+  errors::LemmyAppError,
+  i18n::*,
+  notifications::use_notifications,
+  redirect::use_return_to,
+  ui::components::{
+    common::sort::{sort_type_label, SORT_TYPES},
+    settings::{
+      change_email_form::ChangeEmailForm,
+      change_password_form::ChangePasswordForm,
+      totp_form::TotpForm,
+    },
+  },
+};
+use lemmy_api_common::{
+  lemmy_db_schema::{
+    source::{language::Language, local_user::LocalUser},
+    ListingType,
+  },
+  site::{GetSiteResponse, MyUserInfo},
+  LemmyErrorType,
+};
+use leptos::*;
+use leptos_router::*;
+
+/// The themes of this ui. An account may have saved one of another ui, which is left alone.
+pub const THEMES: [&str; 3] = ["dark", "light", "retro"];
+
+/// What lemmy saves for a theme or language left to the browser.
+pub const BROWSER_DEFAULT: &str = "browser";
+
+// the interface languages, named in themselves
+const LANGUAGES: [(&str, &str); 2] = [("en", "English"), ("fr", "Français")];
+
+const LISTING_TYPES: [ListingType; 3] = [
+  ListingType::All,
+  ListingType::Local,
+  ListingType::Subscribed,
+];
+
+/// The theme the account saved, where this ui has it.
+pub fn saved_theme(local_user: &LocalUser) -> Option<String> {
+  THEMES
+    .contains(&local_user.theme.as_str())
+    .then(|| local_user.theme.clone())
+}
+
+/// The interface language the account saved, where this ui is translated into it.
+pub fn saved_locale(local_user: &LocalUser) -> Option<Locale> {
+  match local_user.interface_language.as_str() {
+    "en" => Some(Locale::en),
+    "fr" => Some(Locale::fr),
+    _ => None,
+  }
+}
+
+/// Points the theme and language cookies at what the account saved, so that every page after login
+/// or saving the settings renders with them. Where the account leaves either to the browser, its
+/// cookie goes.
+#[cfg(feature = "ssr")]
+pub async fn set_ui_cookies(local_user: &LocalUser) -> LemmyAppResult<()> {
+  let max_age = use_public_config().cookie.max_age();
+
+  if let Some(theme) = saved_theme(local_user) {
+    set_cookie("theme", &theme, &max_age).await?;
+  } else if local_user.theme == BROWSER_DEFAULT {
+    remove_cookie("theme").await?;
+  }
+  if saved_locale(local_user).is_some() {
+    set_cookie("i18n_pref_locale", &local_user.interface_language, &max_age).await?;
+  } else if local_user.interface_language == BROWSER_DEFAULT {
+    remove_cookie("i18n_pref_locale").await?;
+  }
+
+  Ok(())
+}
+
+#[server(SaveUserSettingsFn, "/serverfn")]
+#[allow(clippy::too_many_arguments)]
+pub async fn save_user_settings_fn(
+  display_name: String,
+  bio: String,
+  avatar: String,
+  banner: String,
+  show_nsfw: Option<bool>,
+  show_bot_accounts: Option<bool>,
+  show_read_posts: Option<bool>,
+  bot_account: Option<bool>,
+  default_listing_type: String,
+  default_sort_type: String,
+  theme: String,
+  interface_language: String,
+  discussion_languages: Option<Vec<i32>>,
+  return_to: Option<String>,
+) -> Result<GetSiteResponse, ServerFnError<LemmyAppError>> {
+  use crate::lemmy_client::*;
+  use lemmy_api_common::{lemmy_db_schema::newtypes::LanguageId, person::SaveUserSettings};
+
+  // the whole form is sent, so an unchecked box is a false
+  let form = SaveUserSettings {
+    display_name: Some(display_name),
+    bio: Some(bio),
+    avatar: Some(avatar),
+    banner: Some(banner),
+    show_nsfw: Some(show_nsfw.unwrap_or_default()),
+    show_bot_accounts: Some(show_bot_accounts.unwrap_or_default()),
+    show_read_posts: Some(show_read_posts.unwrap_or_default()),
+    bot_account: Some(bot_account.unwrap_or_default()),
+    default_listing_type: serde_json::from_str(&default_listing_type).ok(),
+    default_sort_type: serde_json::from_str(&default_sort_type).ok(),
+    theme: Some(theme),
+    interface_language: Some(interface_language),
+    discussion_languages: Some(
+      discussion_languages
+        .unwrap_or_default()
+        .into_iter()
+        .map(LanguageId)
+        .collect(),
+    ),
+    ..Default::default()
+  };
+
+  let client = use_lemmy_client();
+  let result: LemmyAppResult<GetSiteResponse> = async {
+    client.save_user_settings(form).await?;
+    // what lemmy made of it, for the hydrated app to show
+    let site = client.get_site().await?;
+    if let Some(my_user) = &site.my_user {
+      set_ui_cookies(&my_user.local_user_view.local_user).await?;
+    }
+    Ok(site)
+  }
+  .await;
+
+  let back = return_path(return_to, None).await;
+
+  finish_form(result, &back, None).await
+}
+
+#[component]
+pub fn SettingsActivity(
+  site_signal: RwSignal<Option<Result<GetSiteResponse, LemmyAppError>>>,
+) -> impl IntoView {
+  let i18n = use_i18n();
+
+  // nobody to edit the settings of fails the whole page, see `routed_error_page`
+  let settings = move || {
+    site_signal.get().map(|site| {
+      site.and_then(|site| {
+        let my_user = site
+          .my_user
+          .ok_or_else(|| LemmyAppError::from(LemmyErrorType::NotLoggedIn))?;
+        let email = my_user
+          .local_user_view
+          .local_user
+          .email
+          .clone()
+          .map(|e| e.into_inner())
+          .unwrap_or_default();
+        let totp_enabled = my_user.local_user_view.local_user.totp_2fa_enabled;
+        Ok(view! {
+          <SettingsForm my_user all_languages=site.all_languages site_signal/>
+          // each on its own, as lemmy checks them apart from the other settings
+          <div class="divider"></div>
+          <ChangeEmailForm email site_signal/>
+          <div class="divider"></div>
+          <ChangePasswordForm/>
+          <div class="divider"></div>
+          <TotpForm enabled=totp_enabled site_signal/>
+        })
+      })
+    })
+  };
+
+  view! {
+    <main class="mx-auto">
+      <h2 class="p-6 text-4xl">{t!(i18n, settings)}</h2>
+      {settings}
+    </main>
+  }
+}
+
+#[component]
+fn SettingsForm(
+  my_user: MyUserInfo,
+  all_languages: Vec<Language>,
+  site_signal: RwSignal<Option<Result<GetSiteResponse, LemmyAppError>>>,
+) -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let ui_theme = expect_context::<RwSignal<Option<String>>>();
+  let return_to = use_return_to();
+
+  let person = my_user.local_user_view.person;
+  let local_user = my_user.local_user_view.local_user;
+  let discussion_languages = my_user.discussion_languages;
+
+  // with javascript the form is sent by the action itself, so there is one way to save
+  let save_action = create_server_action::<SaveUserSettingsFn>();
+
+  create_effect(move |_| match save_action.value().get() {
+    Some(Ok(site)) => {
+      notifications.success(t!(i18n, settings_saved)().to_string());
+      if let Some(local_user) = site.my_user.as_ref().map(|m| &m.local_user_view.local_user) {
+        if let Some(theme) = saved_theme(local_user) {
+          ui_theme.set(Some(theme));
+        } else if local_user.theme == BROWSER_DEFAULT {
+          ui_theme.set(None);
+        }
+        if let Some(locale) = saved_locale(local_user) {
+          i18n.set_locale(locale);
+        }
+      }
+      // renders this form anew with what lemmy saved
+      site_signal.set(Some(Ok(site)));
+    }
+    Some(Err(e)) => {
+      notifications.error(e.into());
+    }
+    None => {}
+  });
+
+  let theme = local_user.theme.clone();
+  let interface_language = local_user.interface_language.clone();
+
+  view! {
+    <ActionForm action=save_action class="px-6 flex flex-col gap-6 max-w-2xl">
+      <input type="hidden" name="return_to" value=return_to/>
+      <fieldset class="flex flex-col gap-3">
+        <legend class="text-2xl mb-3">{t!(i18n, account)}</legend>
+        <label class="form-control">
+          <span class="label-text">{t!(i18n, display_name)}</span>
+          <input
+            class="input input-bordered"
+            type="text"
+            name="display_name"
+            value=person.display_name.unwrap_or_default()
+          />
+        </label>
+        <label class="form-control">
+          <span class="label-text">{t!(i18n, bio)}</span>
+          <textarea class="textarea textarea-bordered" name="bio">
+            {person.bio.unwrap_or_default()}
+          </textarea>
+        </label>
+        <label class="form-control">
+          <span class="label-text">{t!(i18n, avatar)}</span>
+          <input
+            class="input input-bordered"
+            type="url"
+            name="avatar"
+            value=person.avatar.map(|a| a.to_string()).unwrap_or_default()
+          />
+        </label>
+        <label class="form-control">
+          <span class="label-text">{t!(i18n, banner)}</span>
+          <input
+            class="input input-bordered"
+            type="url"
+            name="banner"
+            value=person.banner.map(|b| b.to_string()).unwrap_or_default()
+          />
+        </label>
+        <label class="label cursor-pointer justify-start gap-2">
+          <input
+            type="checkbox"
+            class="checkbox"
+            name="bot_account"
+            value="true"
+            checked=person.bot_account
+          />
+          <span class="label-text">{t!(i18n, bot_account)}</span>
+        </label>
+      </fieldset>
+      <fieldset class="flex flex-col gap-3">
+        <legend class="text-2xl mb-3">{t!(i18n, preferences)}</legend>
+        <label class="label cursor-pointer justify-start gap-2">
+          <input
+            type="checkbox"
+            class="checkbox"
+            name="show_nsfw"
+            value="true"
+            checked=local_user.show_nsfw
+          />
+          <span class="label-text">{t!(i18n, show_nsfw)}</span>
+        </label>
+        <label class="label cursor-pointer justify-start gap-2">
+          <input
+            type="checkbox"
+            class="checkbox"
+            name="show_bot_accounts"
+            value="true"
+            checked=local_user.show_bot_accounts
+          />
+          <span class="label-text">{t!(i18n, show_bot_accounts)}</span>
+        </label>
+        <label class="label cursor-pointer justify-start gap-2">
+          <input
+            type="checkbox"
+            class="checkbox"
+            name="show_read_posts"
+            value="true"
+            checked=local_user.show_read_posts
+          />
+          <span class="label-text">{t!(i18n, show_read_posts)}</span>
+        </label>
+        <label class="form-control">
+          <span class="label-text">{t!(i18n, default_listing_type)}</span>
+          <select class="select select-bordered" name="default_listing_type">
+            {LISTING_TYPES
+                .into_iter()
+                .map(|l| {
+                    view! {
+                      <option
+                        value=serde_json::to_string(&l).unwrap_or_default()
+                        selected=l == local_user.default_listing_type
+                      >
+                        {move || match l {
+                            ListingType::Local => t!(i18n, local)().to_string(),
+                            ListingType::Subscribed => t!(i18n, subscribed)().to_string(),
+                            _ => t!(i18n, all)().to_string(),
+                        }}
+                      </option>
+                    }
+                })
+                .collect_view()}
+          </select>
+        </label>
+        <label class="form-control">
+          <span class="label-text">{t!(i18n, default_sort_type)}</span>
+          <select class="select select-bordered" name="default_sort_type">
+            {SORT_TYPES
+                .into_iter()
+                .map(|s| {
+                    view! {
+                      <option
+                        value=serde_json::to_string(&s).unwrap_or_default()
+                        selected=s == local_user.default_sort_type
+                      >
+                        {move || sort_type_label(s)}
+                      </option>
+                    }
+                })
+                .collect_view()}
+          </select>
+        </label>
+        <label class="form-control">
+          <span class="label-text">{t!(i18n, theme)}</span>
+          <select class="select select-bordered" name="theme">
+            <option value=BROWSER_DEFAULT selected=!THEMES.contains(&theme.as_str())>
+              {t!(i18n, browser_default)}
+            </option>
+            {THEMES
+                .into_iter()
+                .map(|name| view! { <option value=name selected=name == theme>{name}</option> })
+                .collect_view()}
+          </select>
+        </label>
+        <label class="form-control">
+          <span class="label-text">{t!(i18n, interface_language)}</span>
+          <select class="select select-bordered" name="interface_language">
+            <option
+              value=BROWSER_DEFAULT
+              selected=!LANGUAGES.iter().any(|(code, _)| *code == interface_language)
+            >
+              {t!(i18n, browser_default)}
+            </option>
+            {LANGUAGES
+                .into_iter()
+                .map(|(code, name)| {
+                    view! {
+                      <option value=code selected=code == interface_language>
+                        {name}
+                      </option>
+                    }
+                })
+                .collect_view()}
+          </select>
+        </label>
+        <div class="form-control">
+          <span class="label-text">{t!(i18n, discussion_languages)}</span>
+          // indexed by id, as unchecked boxes leave gaps that an index sorts out
+          <div class="max-h-60 overflow-y-auto border border-base-300 rounded-box p-3">
+            {all_languages
+                .into_iter()
+                .map(|l| {
+                    view! {
+                      <label class="label cursor-pointer justify-start gap-2">
+                        <input
+                          type="checkbox"
+                          class="checkbox checkbox-sm"
+                          name=format!("discussion_languages[{}]", l.id.0)
+                          value=format!("{}", l.id.0)
+                          checked=discussion_languages.contains(&l.id)
+                        />
+                        <span class="label-text">{l.name}</span>
+                      </label>
+                    }
+                })
+                .collect_view()}
+          </div>
+        </div>
+      </fieldset>
+      <button type="submit" class="btn btn-primary self-start">
+        {t!(i18n, save)}
+      </button>
+    </ActionForm>
+  }
+}
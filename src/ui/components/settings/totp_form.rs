@@ -0,0 +1,275 @@
+#[cfg(feature = "ssr")]
+use crate::{
+  cookie::remove_cookie,
+  errors::LemmyAppResult,
+  flash::{finish_form, get_private_cookie, set_private_cookie},
+  lemmy_client::*,
+  redirect::return_path,
+};
+use crate::{
+  errors::{LemmyAppError, LemmyAppErrorType},
+  flash::use_flash,
+  i18n::*,
+  notifications::use_notifications,
+  redirect::use_return_to,
+  ui::components::common::qr_code::{QrCode, QrSvg},
+};
+#[cfg(feature = "ssr")]
+use core::time::Duration;
+use lemmy_api_common::{site::GetSiteResponse, LemmyErrorType};
+use leptos::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+const TOTP_SETUP_PATH: &str = "/settings?totp=setup";
+
+// keeps the secret being set up over the pages of the setup, so that a wrong code is tried again
+// against the same secret rather than a new one
+#[cfg(feature = "ssr")]
+const TOTP_SECRET_COOKIE: &str = "totp_secret";
+
+#[cfg(feature = "ssr")]
+const TOTP_SECRET_MAX_AGE: Duration = Duration::from_secs(15 * 60);
+
+/// A two-factor secret being set up: the link authenticator apps take, and its QR code.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TotpSetup {
+  secret_url: String,
+  qr_code: Option<QrSvg>,
+}
+
+#[cfg(feature = "ssr")]
+impl TotpSetup {
+  fn new(secret_url: String) -> Self {
+    let qr_code = QrSvg::new(&secret_url);
+    Self {
+      secret_url,
+      qr_code,
+    }
+  }
+}
+
+/// Has lemmy make a new secret, which it only keeps once a code of it is confirmed, and keeps it
+/// for the setup page.
+#[server(GenerateTotpSecretFn, "/serverfn")]
+pub async fn generate_totp_secret_fn(
+  return_to: Option<String>,
+) -> Result<TotpSetup, ServerFnError<LemmyAppError>> {
+  let result: LemmyAppResult<TotpSetup> = async {
+    let secret_url = use_lemmy_client()
+      .generate_totp_secret()
+      .await?
+      .totp_secret_url
+      .into_inner();
+    set_private_cookie(TOTP_SECRET_COOKIE, &secret_url, &TOTP_SECRET_MAX_AGE).await?;
+    Ok(TotpSetup::new(secret_url))
+  }
+  .await;
+
+  let back = return_path(return_to, None).await;
+
+  finish_form(result, &back, Some(TOTP_SETUP_PATH)).await
+}
+
+/// The secret being set up, where one was asked for and has not expired.
+#[server(TotpSetupFn, "/serverfn")]
+pub async fn totp_setup_fn() -> Result<Option<TotpSetup>, ServerFnError<LemmyAppError>> {
+  Ok(
+    get_private_cookie(TOTP_SECRET_COOKIE)
+      .await
+      .map(TotpSetup::new),
+  )
+}
+
+/// Turns two-factor authentication on or off, confirmed by a code of the authenticator app. Once
+/// done, goes back to the settings without the setup, whatever page the form was on.
+#[server(UpdateTotpFn, "/serverfn")]
+pub async fn update_totp_fn(
+  totp_token: String,
+  enabled: bool,
+  return_to: Option<String>,
+) -> Result<GetSiteResponse, ServerFnError<LemmyAppError>> {
+  use lemmy_api_common::person::UpdateTotp;
+
+  let result: LemmyAppResult<GetSiteResponse> = async {
+    if totp_token.trim().is_empty() {
+      return Err(LemmyErrorType::MissingTotpToken.into());
+    }
+
+    let client = use_lemmy_client();
+    client
+      .update_totp(UpdateTotp {
+        totp_token: totp_token.trim().to_string(),
+        enabled,
+      })
+      .await?;
+    // set up or turned off, either way there is nothing left to set up
+    remove_cookie(TOTP_SECRET_COOKIE).await?;
+    // what lemmy made of it, for the hydrated app to show
+    client.get_site().await
+  }
+  .await;
+
+  let back = return_path(return_to, None).await;
+
+  finish_form(result, &back, Some("/settings")).await
+}
+
+/// Two-factor authentication: a way to turn it off where it is on, otherwise a way to set it up.
+/// A secret is only made when asked for, and shown at `?totp=setup` so that it works without
+/// javascript too.
+#[component]
+pub fn TotpForm(
+  enabled: bool,
+  site_signal: RwSignal<Option<Result<GetSiteResponse, LemmyAppError>>>,
+) -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let return_to = use_return_to();
+  let navigate = store_value(use_navigate());
+
+  let query = use_query_map();
+  let setup = move || !enabled && query.get().get("totp").is_some_and(|t| t == "setup");
+
+  let generate_action = create_server_action::<GenerateTotpSecretFn>();
+
+  // the secret asked for last, nothing until one is
+  let totp_setup = create_resource(
+    move || (setup(), generate_action.version().get()),
+    move |(setup, _version)| async move {
+      if !setup {
+        return None;
+      }
+      totp_setup_fn().await.ok().flatten()
+    },
+  );
+
+  create_effect(move |_| match generate_action.value().get() {
+    Some(Ok(_)) => {
+      navigate.with_value(|navigate| navigate(TOTP_SETUP_PATH, Default::default()));
+    }
+    Some(Err(e)) => {
+      notifications.error(e.into());
+    }
+    None => {}
+  });
+
+  let validation = create_rw_signal::<String>("".into());
+  if let Some(LemmyAppError {
+    error_type:
+      LemmyAppErrorType::ApiError(
+        LemmyErrorType::MissingTotpToken | LemmyErrorType::IncorrectTotpToken,
+      ),
+    ..
+  }) = use_flash().error()
+  {
+    validation.set("input-error".to_string());
+  }
+
+  let update_action = create_server_action::<UpdateTotpFn>();
+
+  create_effect(move |_| match update_action.value().get() {
+    Some(Ok(site)) => {
+      let on = site
+        .my_user
+        .as_ref()
+        .is_some_and(|m| m.local_user_view.local_user.totp_2fa_enabled);
+      if on {
+        notifications.success(t!(i18n, totp_enabled)().to_string());
+      } else {
+        notifications.success(t!(i18n, totp_disabled)().to_string());
+      }
+      navigate.with_value(|navigate| navigate("/settings", Default::default()));
+      // renders the settings anew with what lemmy saved
+      site_signal.set(Some(Ok(site)));
+    }
+    Some(Err(e)) => {
+      validation.set("input-error".to_string());
+      notifications.error(e.into());
+    }
+    None => {}
+  });
+
+  let code_input = move || {
+    view! {
+      <label class="form-control">
+        <span class="label-text">{t!(i18n, totp_code)}</span>
+        <input
+          class=move || format!("input input-bordered {}", validation.get())
+          type="text"
+          name="totp_token"
+          inputmode="numeric"
+          autocomplete="one-time-code"
+          required
+        />
+      </label>
+    }
+  };
+
+  view! {
+    <section class="px-6 flex flex-col gap-3 max-w-2xl">
+      <h3 class="text-2xl mb-3">{t!(i18n, two_factor)}</h3>
+      {move || {
+          if enabled {
+              view! {
+                <p>{t!(i18n, totp_enabled)}</p>
+                <ActionForm action=update_action class="flex flex-col gap-3">
+                  <input type="hidden" name="enabled" value="false"/>
+                  <input type="hidden" name="return_to" value=return_to/>
+                  {code_input}
+                  <button type="submit" class="btn btn-error self-start">
+                    {t!(i18n, disable_totp)}
+                  </button>
+                </ActionForm>
+              }
+                  .into_view()
+          } else {
+              view! {
+                <Transition fallback=|| {}>
+                  {move || match totp_setup.get().flatten() {
+                      Some(TotpSetup { secret_url, qr_code }) => {
+                          view! {
+                            <p>{t!(i18n, totp_scan)}</p>
+                            {qr_code.map(|code| view! { <QrCode code class="w-48 h-48"/> })}
+                            // for apps that take the secret typed in
+                            <details>
+                              <summary class="cursor-pointer">{t!(i18n, totp_link)}</summary>
+                              <code class="break-all">{secret_url}</code>
+                            </details>
+                            <ActionForm action=update_action class="flex flex-col gap-3">
+                              <input type="hidden" name="enabled" value="true"/>
+                              <input type="hidden" name="return_to" value=return_to/>
+                              {code_input}
+                              <button type="submit" class="btn btn-primary self-start">
+                                {t!(i18n, enable_totp)}
+                              </button>
+                            </ActionForm>
+                            <A href="/settings" class="link">
+                              {t!(i18n, cancel)}
+                            </A>
+                          }
+                              .into_view()
+                      }
+                      None => {
+                          view! {
+                            <p>{t!(i18n, totp_disabled)}</p>
+                            <ActionForm action=generate_action>
+                              <input type="hidden" name="return_to" value=return_to/>
+                              <button type="submit" class="btn self-start">
+                                {t!(i18n, enable_totp)}
+                              </button>
+                            </ActionForm>
+                          }
+                              .into_view()
+                      }
+                  }}
+
+                </Transition>
+              }
+                  .into_view()
+          }
+      }}
+
+    </section>
+  }
+}
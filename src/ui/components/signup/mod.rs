@@ -0,0 +1,2 @@
+pub mod signup_activity;
+pub mod signup_form;
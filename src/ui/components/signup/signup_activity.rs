@@ -0,0 +1,62 @@
+use crate::{errors::LemmyAppError, i18n::*, ui::components::signup::signup_form::SignupForm};
+use lemmy_api_common::{lemmy_db_schema::RegistrationMode, site::GetSiteResponse};
+use leptos::*;
+use leptos_router::*;
+
+#[component]
+pub fn SignupActivity(
+  site_signal: RwSignal<Option<Result<GetSiteResponse, LemmyAppError>>>,
+) -> impl IntoView {
+  let i18n = use_i18n();
+
+  let query = use_query_map();
+
+  // what is left to do after signing up, see `follow_up`
+  let verify_email = move || query.get().get("verify_email").is_some_and(|v| v == "true");
+  let application = move || query.get().get("application").is_some_and(|a| a == "true");
+
+  let local_site = move || {
+    site_signal
+      .get()
+      .and_then(Result::ok)
+      .map(|s| s.site_view.local_site)
+  };
+
+  view! {
+    <main class="mx-auto max-w-screen-md p-3">
+      <h2 class="text-4xl mb-3">{t!(i18n, signup)}</h2>
+      {move || {
+          if verify_email() || application() {
+              view! { <SignupDone verify_email=verify_email() application=application()/> }
+                  .into_view()
+          } else {
+              local_site()
+                  .map(|local_site| {
+                      if matches!(local_site.registration_mode, RegistrationMode::Closed) {
+                          view! { <p>{t!(i18n, registration_closed)}</p> }.into_view()
+                      } else {
+                          view! { <SignupForm local_site/> }.into_view()
+                      }
+                  })
+                  .into_view()
+          }
+      }}
+
+    </main>
+  }
+}
+
+#[component]
+fn SignupDone(verify_email: bool, application: bool) -> impl IntoView {
+  let i18n = use_i18n();
+
+  view! {
+    <div class="flex flex-col gap-3">
+      {verify_email.then(|| view! { <p>{t!(i18n, check_email)}</p> })}
+      {application.then(|| view! { <p>{t!(i18n, application_pending)}</p> })}
+      <A href="/login" class="btn self-start">
+        {t!(i18n, login)}
+      </A>
+    </div>
+  }
+}
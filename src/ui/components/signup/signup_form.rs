@@ -0,0 +1,379 @@
+#[cfg(feature = "ssr")]
+use crate::{
+  config::use_public_config,
+  cookie::set_auth_cookie,
+  errors::LemmyAppResult,
+  flash::finish_form,
+};
+use crate::{
+  errors::{LemmyAppError, LemmyAppErrorType},
+  flash::use_flash,
+  i18n::*,
+  lemmy_client::*,
+  notifications::use_notifications,
+  ui::components::common::text_input::{InputType, TextInput},
+};
+use lemmy_api_common::{
+  lemmy_db_schema::{source::local_site::LocalSite, RegistrationMode},
+  person::{LoginResponse, Register},
+  LemmyErrorType,
+};
+use leptos::*;
+use leptos_router::*;
+use web_sys::SubmitEvent;
+
+fn validate_register(form: &Register) -> Option<LemmyAppErrorType> {
+  if form.username.is_empty() {
+    return Some(LemmyAppErrorType::EmptyUsername);
+  }
+  if form.password.is_empty() {
+    return Some(LemmyAppErrorType::EmptyPassword);
+  }
+  if *form.password != *form.password_verify {
+    return Some(LemmyAppErrorType::PasswordMismatch);
+  }
+  // only asked for when the site wants them
+  if form.answer.as_deref() == Some("") {
+    return Some(LemmyAppErrorType::EmptyAnswer);
+  }
+  if form.captcha_answer.as_deref() == Some("") {
+    return Some(LemmyAppErrorType::EmptyCaptchaAnswer);
+  }
+  None
+}
+
+/// The name of the field an error is about, ours or lemmy's, to mark it in the form.
+fn invalid_field(error_type: &LemmyAppErrorType) -> Option<&'static str> {
+  match error_type {
+    LemmyAppErrorType::EmptyUsername
+    | LemmyAppErrorType::ApiError(
+      LemmyErrorType::UserAlreadyExists | LemmyErrorType::InvalidName,
+    ) => Some("username"),
+    LemmyAppErrorType::EmptyPassword
+    | LemmyAppErrorType::ApiError(LemmyErrorType::InvalidPassword) => Some("password"),
+    LemmyAppErrorType::PasswordMismatch
+    | LemmyAppErrorType::ApiError(LemmyErrorType::PasswordsDoNotMatch) => Some("password_verify"),
+    LemmyAppErrorType::ApiError(
+      LemmyErrorType::EmailRequired | LemmyErrorType::EmailAlreadyExists,
+    ) => Some("email"),
+    LemmyAppErrorType::EmptyAnswer
+    | LemmyAppErrorType::ApiError(LemmyErrorType::RegistrationApplicationAnswerRequired) => {
+      Some("answer")
+    }
+    LemmyAppErrorType::EmptyCaptchaAnswer
+    | LemmyAppErrorType::ApiError(LemmyErrorType::CaptchaIncorrect) => Some("captcha_answer"),
+    _ => None,
+  }
+}
+
+/// Where a registration leads: in right away when lemmy hands out a session, otherwise to what is
+/// still to be done before one can log in.
+fn follow_up(res: &LoginResponse) -> String {
+  if res.jwt.is_some() {
+    return "/".to_string();
+  }
+
+  let mut params = vec![];
+  if res.verify_email_sent {
+    params.push("verify_email=true");
+  }
+  if res.registration_created {
+    params.push("application=true");
+  }
+
+  if params.is_empty() {
+    "/login".to_string()
+  } else {
+    format!("/signup?{}", params.join("&"))
+  }
+}
+
+#[cfg(feature = "ssr")]
+async fn try_register(client: LemmyClient, form: Register) -> Result<LoginResponse, LemmyAppError> {
+  match validate_register(&form) {
+    None => client.register(form).await,
+    Some(e) => Err(LemmyAppError {
+      error_type: e.clone(),
+      content: format!("{:#?}", e),
+    }),
+  }
+}
+
+/// Registers the account and, when lemmy lets it in right away, logs it in. Answers where to go
+/// next rather than the session, which stays in the HttpOnly cookie.
+#[server(RegisterFn, "/serverfn")]
+#[allow(clippy::too_many_arguments)]
+pub async fn register(
+  username: String,
+  password: String,
+  password_verify: String,
+  email: Option<String>,
+  answer: Option<String>,
+  captcha_uuid: Option<String>,
+  captcha_answer: Option<String>,
+  honeypot: Option<String>,
+) -> Result<String, ServerFnError<LemmyAppError>> {
+  let form = Register {
+    username,
+    password: password.into(),
+    password_verify: password_verify.into(),
+    email: email.filter(|e| !e.is_empty()).map(Into::into),
+    answer,
+    captcha_uuid,
+    captcha_answer,
+    honeypot: honeypot.filter(|h| !h.is_empty()),
+    ..Default::default()
+  };
+
+  let result: LemmyAppResult<String> = async {
+    let o = try_register(use_lemmy_client(), form).await?;
+    if let Some(jwt) = o.jwt.clone() {
+      set_auth_cookie(&jwt.into_inner(), &use_public_config().cookie.max_age()).await?;
+    }
+    Ok(follow_up(&o))
+  }
+  .await;
+
+  // on to where `follow_up` says, the form again on errors
+  let next = result.as_ref().ok().cloned();
+  finish_form(result, "/signup", next.as_deref()).await
+}
+
+#[component]
+pub fn SignupForm(local_site: LocalSite) -> impl IntoView {
+  let i18n = use_i18n();
+
+  let notifications = use_notifications();
+  let user = expect_context::<RwSignal<Option<bool>>>();
+  let client = store_value(use_lemmy_client());
+
+  let ask_answer = matches!(
+    local_site.registration_mode,
+    RegistrationMode::RequireApplication
+  );
+  let ask_captcha = local_site.captcha_enabled;
+  let email_required = local_site.require_email_verification;
+
+  let username = create_rw_signal(String::new());
+  let password = create_rw_signal(String::new());
+  let password_verify = create_rw_signal(String::new());
+  let email = create_rw_signal(String::new());
+  let answer = create_rw_signal(String::new());
+  let captcha_answer = create_rw_signal(String::new());
+
+  let register_action = create_server_action::<RegisterFn>();
+  let navigate = store_value(use_navigate());
+
+  let invalid = create_rw_signal::<Option<&'static str>>(None);
+  let validation = move |field: &'static str| {
+    Signal::derive(move || {
+      if invalid.get() == Some(field) {
+        "input-error".to_string()
+      } else {
+        "".to_string()
+      }
+    })
+  };
+
+  if let Some(e) = use_flash().error() {
+    invalid.set(invalid_field(&e.error_type));
+  }
+
+  // a captcha is good for one try
+  let captcha = create_resource(
+    move || (),
+    move |()| async move {
+      if !ask_captcha {
+        return None;
+      }
+      let result = client.get_value().get_captcha().await;
+      result.ok().and_then(|c| c.ok)
+    },
+  );
+
+  let on_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let captcha_uuid = captcha.get_untracked().flatten().map(|c| c.uuid);
+        let form = Register {
+          username: username.get_untracked(),
+          password: password.get_untracked().into(),
+          password_verify: password_verify.get_untracked().into(),
+          answer: ask_answer.then(|| answer.get_untracked()),
+          captcha_answer: ask_captcha.then(|| captcha_answer.get_untracked()),
+          ..Default::default()
+        };
+
+        let result = match validate_register(&form) {
+          Some(e) => Err(LemmyAppError::from(e)),
+          // only the server can set the HttpOnly session cookie
+          None => register(
+            form.username,
+            password.get_untracked(),
+            password_verify.get_untracked(),
+            Some(email.get_untracked()),
+            form.answer,
+            captcha_uuid,
+            form.captcha_answer,
+            None,
+          )
+          .await
+          .map_err(LemmyAppError::from),
+        };
+
+        match result {
+          Ok(next) => {
+            if next == "/" {
+              user.set(Some(true));
+            }
+            navigate.with_value(|navigate| navigate(&next, Default::default()));
+          }
+          Err(e) => {
+            invalid.set(invalid_field(&e.error_type));
+            notifications.error(e);
+            if ask_captcha {
+              captcha.refetch();
+            }
+          }
+        }
+      },
+    );
+  };
+
+  view! {
+    <ActionForm class="space-y-3" action=register_action on:submit=on_submit>
+      <TextInput
+        id="username"
+        name="username"
+        required=true
+        validation_class=validation("username").into()
+        on_input=move |s| update!(| username | * username = s)
+        label=move || t!(i18n, username)().to_string()
+      />
+      <TextInput
+        id="email"
+        name="email"
+        required=email_required
+        validation_class=validation("email").into()
+        on_input=move |s| update!(| email | * email = s)
+        label=move || {
+            if email_required {
+                t!(i18n, email)().to_string()
+            } else {
+                t!(i18n, email_optional)().to_string()
+            }
+        }
+      />
+      <TextInput
+        id="password"
+        name="password"
+        required=true
+        validation_class=validation("password").into()
+        on_input=move |s| update!(| password | * password = s)
+        input_type=InputType::Password
+        label=move || t!(i18n, password)().to_string()
+      />
+      <TextInput
+        id="password_verify"
+        name="password_verify"
+        required=true
+        validation_class=validation("password_verify").into()
+        on_input=move |s| update!(| password_verify | * password_verify = s)
+        input_type=InputType::Password
+        label=move || t!(i18n, password_verify)().to_string()
+      />
+      {ask_answer
+          .then(|| {
+              view! {
+                <label class="form-control !mt-8">
+                  <span class="label-text whitespace-pre-wrap">
+                    {local_site.application_question.clone().unwrap_or_default()}
+                  </span>
+                  <textarea
+                    class=move || {
+                        if invalid.get() == Some("answer") {
+                            "textarea textarea-bordered textarea-error"
+                        } else {
+                            "textarea textarea-bordered"
+                        }
+                    }
+                    name="answer"
+                    required
+                    prop:value=answer
+                    on:input=move |e| answer.set(event_target_value(&e))
+                  ></textarea>
+                </label>
+              }
+          })}
+      {ask_captcha
+          .then(|| {
+              view! {
+                <Transition fallback=|| {}>
+                  {move || {
+                      captcha
+                          .get()
+                          .flatten()
+                          .map(|c| {
+                              view! {
+                                <div class="flex flex-col gap-2 !mt-8">
+                                  <img
+                                    class="self-start"
+                                    src=format!("data:image/png;base64,{}", c.png)
+                                    alt=move || t!(i18n, captcha)().to_string()
+                                  />
+                                  // for those who cannot read the image
+                                  <audio
+                                    controls
+                                    src=format!("data:audio/wav;base64,{}", c.wav)
+                                  ></audio>
+                                  <input type="hidden" name="captcha_uuid" value=c.uuid/>
+                                  <button
+                                    type="button"
+                                    class="btn btn-sm self-start"
+                                    on:click=move |_| captcha.refetch()
+                                  >
+                                    {t!(i18n, new_captcha)}
+                                  </button>
+                                </div>
+                              }
+                          })
+                  }}
+
+                </Transition>
+                <TextInput
+                  id="captcha_answer"
+                  name="captcha_answer"
+                  required=true
+                  validation_class=validation("captcha_answer").into()
+                  on_input=move |s| update!(| captcha_answer | * captcha_answer = s)
+                  label=move || t!(i18n, captcha)().to_string()
+                />
+              }
+          })}
+      // left empty by people, filled in by bots
+      <input type="text" name="honeypot" class="hidden" tabindex="-1" autocomplete="off"/>
+      {local_site
+          .legal_information
+          .clone()
+          .map(|legal| {
+              view! {
+                <details class="!mt-8">
+                  <summary class="cursor-pointer">{t!(i18n, legal_information)}</summary>
+                  <p class="whitespace-pre-wrap">{legal}</p>
+                </details>
+              }
+          })}
+      // the browser asks for this before submitting, with or without javascript
+      <label class="label cursor-pointer justify-start gap-2 !mt-8">
+        <input type="checkbox" class="checkbox" required/>
+        <span class="label-text">{t!(i18n, signup_acknowledgement)}</span>
+      </label>
+      <button class="btn btn-lg" type="submit">
+        {t!(i18n, signup)}
+      </button>
+    </ActionForm>
+  }
+}
@@ -0,0 +1,83 @@
+use crate::{
+  errors::LemmyAppError,
+  i18n::*,
+  lemmy_client::*,
+  notifications::use_notifications,
+  redirect::use_return_to,
+};
+#[cfg(feature = "ssr")]
+use crate::{flash::finish_form, redirect::return_path};
+use lemmy_api_common::person::VerifyEmail;
+use leptos::*;
+use leptos_router::*;
+use web_sys::SubmitEvent;
+
+/// Verifies the email address a link was sent to. Errors go back to the page of that link, through
+/// `return_to`.
+#[server(VerifyEmailFn, "/serverfn")]
+pub async fn verify_email_fn(
+  token: String,
+  return_to: Option<String>,
+) -> Result<(), ServerFnError<LemmyAppError>> {
+  let result = use_lemmy_client().verify_email(VerifyEmail { token }).await;
+
+  let back = return_path(return_to, None).await;
+
+  finish_form(
+    result.map(|_| ()),
+    &back,
+    Some("/login?email_verified=true"),
+  )
+  .await
+}
+
+// the address is only verified once it is confirmed here, not by merely opening the link, which
+// mail scanners do too
+#[component]
+pub fn VerifyEmailActivity() -> impl IntoView {
+  let i18n = use_i18n();
+  let notifications = use_notifications();
+  let client = store_value(use_lemmy_client());
+  let return_to = use_return_to();
+
+  let params = use_params_map();
+  let token = Signal::derive(move || params.get().get("token").cloned().unwrap_or_default());
+
+  let verify_action = create_server_action::<VerifyEmailFn>();
+  let navigate = store_value(use_navigate());
+
+  let on_submit = move |ev: SubmitEvent| {
+    ev.prevent_default();
+
+    create_local_resource(
+      move || (),
+      move |()| async move {
+        let form = VerifyEmail {
+          token: token.get_untracked(),
+        };
+
+        match client.get_value().verify_email(form).await {
+          Ok(_o) => {
+            navigate
+              .with_value(|navigate| navigate("/login?email_verified=true", Default::default()));
+          }
+          Err(e) => notifications.error(e),
+        }
+      },
+    );
+  };
+
+  view! {
+    <main class="mx-auto max-w-screen-md p-3">
+      <h2 class="text-4xl mb-3">{t!(i18n, verify_email)}</h2>
+      <ActionForm class="space-y-3" action=verify_action on:submit=on_submit>
+        <p>{t!(i18n, verify_email_prompt)}</p>
+        <input type="hidden" name="token" value=token/>
+        <input type="hidden" name="return_to" value=return_to/>
+        <button class="btn btn-lg" type="submit">
+          {t!(i18n, verify_email)}
+        </button>
+      </ActionForm>
+    </main>
+  }
+}
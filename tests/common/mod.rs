@@ -0,0 +1,346 @@
+// each integration test binary only uses part of the harness
+#![allow(dead_code)]
+
+use actix_web::{
+  http::{header, StatusCode},
+  web,
+  App,
+  HttpRequest,
+  HttpResponse,
+  HttpServer,
+};
+use awc::Client;
+use lemmy_ui_leptos::{config::AppConfig, host::ClientOptions, lemmy_client::REQUEST_ID_HEADER};
+use leptos::{create_runtime, provide_context, RuntimeId};
+use std::{
+  collections::HashMap,
+  net::SocketAddr,
+  sync::{Arc, Mutex},
+};
+
+/// credentials accepted by `user/login`, the same as the docker-compose instance in `end2end/`
+pub const USERNAME: &str = "lemmy";
+pub const PASSWORD: &str = "lemmylemmy";
+/// the jwt handed out on a successful login
+pub const JWT: &str = "mock.jwt.token";
+
+pub const SITE: &str = include_str!("../fixtures/site.json");
+/// the site as seen by the logged in admin `USERNAME`
+pub const SITE_USER: &str = include_str!("../fixtures/site_user.json");
+pub const POST_LIST: &str = include_str!("../fixtures/post_list.json");
+pub const POST: &str = include_str!("../fixtures/post.json");
+pub const COMMENT_LIST: &str = include_str!("../fixtures/comment_list.json");
+pub const COMMUNITY_LIST: &str = include_str!("../fixtures/community_list.json");
+pub const COMMUNITY: &str = include_str!("../fixtures/community.json");
+pub const COMMUNITY_FOLLOW: &str = include_str!("../fixtures/community_follow.json");
+pub const SEARCH: &str = include_str!("../fixtures/search.json");
+/// the profile of `alice`, another user than `USERNAME`
+pub const PERSON_DETAILS: &str = include_str!("../fixtures/person_details.json");
+pub const BAN_PERSON: &str = include_str!("../fixtures/ban_person.json");
+/// the inbox of `USERNAME`, one unread item of each kind from `alice`
+pub const REPLIES: &str = include_str!("../fixtures/replies.json");
+pub const MENTIONS: &str = include_str!("../fixtures/mentions.json");
+pub const PRIVATE_MESSAGES: &str = include_str!("../fixtures/private_messages.json");
+pub const PRIVATE_MESSAGE: &str = include_str!("../fixtures/private_message.json");
+pub const PRIVATE_MESSAGE_REPORT: &str = include_str!("../fixtures/private_message_report.json");
+pub const UNREAD_COUNT: &str = include_str!("../fixtures/unread_count.json");
+pub const REPLY_READ: &str = include_str!("../fixtures/reply_read.json");
+pub const COMMENT: &str = include_str!("../fixtures/comment.json");
+pub const LOGIN: &str = include_str!("../fixtures/login.json");
+/// an account with two-factor authentication on, logging in with `PASSWORD` and `TOTP_TOKEN`
+pub const TOTP_USERNAME: &str = "secure";
+pub const TOTP_TOKEN: &str = "123456";
+pub const TOTP_SECRET: &str =
+  r#"{"totp_secret_url":"otpauth://totp/lemmy:lemmy?secret=JBSWY3DPEHPK3PXP"}"#;
+/// the token of the links lemmy emails for a password reset or an email verification
+pub const EMAIL_TOKEN: &str = "email-token";
+/// a registration that waits for both the email and an admin
+pub const REGISTRATION_PENDING: &str =
+  r#"{"jwt":null,"registration_created":true,"verify_email_sent":true}"#;
+pub const CAPTCHA: &str = r#"{"ok":{"png":"aW1hZ2U=","wav":"YXVkaW8=","uuid":"captcha-uuid"}}"#;
+pub const POST_LIKE: &str = include_str!("../fixtures/post_like.json");
+
+/// A request as the mock backend received it.
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+  pub method: String,
+  /// route below `/api/v3/`, e.g. `post/list`
+  pub route: String,
+  pub query: String,
+  pub authorization: Option<String>,
+  pub request_id: Option<String>,
+  /// the browser address lemmy rate limits by
+  pub forwarded_for: Option<String>,
+  pub body: String,
+}
+
+#[derive(Default)]
+struct MockState {
+  overrides: Mutex<HashMap<String, (u16, String)>>,
+  requests: Mutex<Vec<RecordedRequest>>,
+}
+
+/// An offline stand-in for the Lemmy `/api/v3` backend, serving the JSON in `tests/fixtures/`.
+pub struct MockLemmy {
+  addr: SocketAddr,
+  state: Arc<MockState>,
+}
+
+impl MockLemmy {
+  /// Binds to a free local port and serves on the current actix system until the test ends.
+  pub fn start() -> Self {
+    let state = Arc::new(MockState::default());
+    let data = web::Data::from(state.clone());
+
+    let server = HttpServer::new(move || {
+      App::new()
+        .app_data(data.clone())
+        .default_service(web::to(respond))
+    })
+    .workers(1)
+    .disable_signals()
+    .bind(("127.0.0.1", 0))
+    .expect("mock lemmy should bind to a local port");
+
+    let addr = server.addrs()[0];
+    actix_web::rt::spawn(server.run());
+
+    Self { addr, state }
+  }
+
+  pub fn client_options(&self) -> ClientOptions {
+    ClientOptions {
+      domain: self.addr.to_string(),
+      secure: false,
+      api_version: "v3".into(),
+    }
+  }
+
+  /// The default config, pointed at this backend.
+  pub fn config(&self) -> AppConfig {
+    let mut config = AppConfig::default();
+    config.lemmy.host = self.addr.to_string();
+    config
+  }
+
+  /// Answers every request for `route` with `status` and `body` instead of the fixture.
+  pub fn respond_with(&self, route: &str, status: u16, body: &str) {
+    self
+      .state
+      .overrides
+      .lock()
+      .unwrap()
+      .insert(route.into(), (status, body.into()));
+  }
+
+  pub fn requests(&self) -> Vec<RecordedRequest> {
+    self.state.requests.lock().unwrap().clone()
+  }
+
+  pub fn requests_to(&self, route: &str) -> Vec<RecordedRequest> {
+    self
+      .requests()
+      .into_iter()
+      .filter(|r| r.route == route)
+      .collect()
+  }
+}
+
+async fn respond(req: HttpRequest, body: web::Bytes, state: web::Data<MockState>) -> HttpResponse {
+  let header_value = |name: &str| {
+    req
+      .headers()
+      .get(name)
+      .and_then(|v| v.to_str().ok())
+      .map(String::from)
+  };
+
+  let route = req
+    .path()
+    .strip_prefix("/api/v3/")
+    .unwrap_or_default()
+    .to_string();
+  let body = String::from_utf8_lossy(&body).into_owned();
+  let authorization = header_value(header::AUTHORIZATION.as_str());
+
+  state.requests.lock().unwrap().push(RecordedRequest {
+    method: req.method().to_string(),
+    route: route.clone(),
+    query: req.query_string().into(),
+    authorization: authorization.clone(),
+    request_id: header_value(REQUEST_ID_HEADER),
+    forwarded_for: header_value(header::X_FORWARDED_FOR.as_str()),
+    body: body.clone(),
+  });
+
+  if let Some((status, body)) = state.overrides.lock().unwrap().get(&route) {
+    if route == "private_message/list" {
+      return json(*status, &by_creator(req.query_string(), body));
+    }
+    return json(*status, body);
+  }
+
+  match route.as_str() {
+    "site" => {
+      if authorization.as_deref() == Some(format!("Bearer {JWT}").as_str()) {
+        json(200, SITE_USER)
+      } else {
+        json(200, SITE)
+      }
+    }
+    "post/list" => json(200, POST_LIST),
+    "post" => {
+      if req.query_string().split('&').any(|p| p == "id=1") {
+        json(200, POST)
+      } else {
+        lemmy_error("couldnt_find_post")
+      }
+    }
+    "comment/list" => json(200, COMMENT_LIST),
+    "community/list" => json(200, COMMUNITY_LIST),
+    "community" => {
+      if req.query_string().split('&').any(|p| p == "name=main") {
+        json(200, COMMUNITY)
+      } else {
+        lemmy_error("couldnt_find_community")
+      }
+    }
+    "community/follow" => logged_in(authorization.as_deref(), COMMUNITY_FOLLOW),
+    "search" => json(200, SEARCH),
+    "user/login" => {
+      let form: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+      if form["username_or_email"] == USERNAME && form["password"] == PASSWORD {
+        json(200, LOGIN)
+      } else if form["username_or_email"] == TOTP_USERNAME && form["password"] == PASSWORD {
+        match form["totp_2fa_token"].as_str() {
+          None => lemmy_error("missing_totp_token"),
+          Some(TOTP_TOKEN) => json(200, LOGIN),
+          Some(_) => lemmy_error("incorrect_totp_token"),
+        }
+      } else {
+        lemmy_error("incorrect_login")
+      }
+    }
+    "user/register" => {
+      let form: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+      match form["username"].as_str() {
+        Some("applicant") => json(200, REGISTRATION_PENDING),
+        Some(USERNAME) => lemmy_error("user_already_exists"),
+        _ => json(200, LOGIN),
+      }
+    }
+    "user/get_captcha" => json(200, CAPTCHA),
+    "user/password_reset" => json(200, r#"{"success":true}"#),
+    "user/password_change" | "user/verify_email" => {
+      let form: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+      if form["token"] == EMAIL_TOKEN {
+        json(200, r#"{"success":true}"#)
+      } else {
+        lemmy_error("token_not_found")
+      }
+    }
+    "user/totp/generate" => logged_in(authorization.as_deref(), TOTP_SECRET),
+    "user/totp/update" => {
+      let form: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+      if form["totp_token"] == TOTP_TOKEN {
+        logged_in(
+          authorization.as_deref(),
+          &format!(r#"{{"enabled":{}}}"#, form["enabled"]),
+        )
+      } else {
+        lemmy_error("incorrect_totp_token")
+      }
+    }
+    "user/change_password" => {
+      let form: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+      if form["old_password"] == PASSWORD {
+        logged_in(authorization.as_deref(), LOGIN)
+      } else {
+        lemmy_error("incorrect_login")
+      }
+    }
+    "user" => {
+      if req
+        .query_string()
+        .split('&')
+        .any(|p| p == "username=alice" || p == "person_id=3")
+      {
+        json(200, PERSON_DETAILS)
+      } else {
+        lemmy_error("couldnt_find_person")
+      }
+    }
+    "user/ban" => logged_in(authorization.as_deref(), BAN_PERSON),
+    "admin/purge/person" => logged_in(authorization.as_deref(), r#"{"success":true}"#),
+    "user/replies" => logged_in(authorization.as_deref(), REPLIES),
+    "user/mention" => logged_in(authorization.as_deref(), MENTIONS),
+    "private_message/list" => logged_in(
+      authorization.as_deref(),
+      &by_creator(req.query_string(), PRIVATE_MESSAGES),
+    ),
+    "private_message" => logged_in(authorization.as_deref(), PRIVATE_MESSAGE),
+    "private_message/delete" => logged_in(authorization.as_deref(), PRIVATE_MESSAGE),
+    "private_message/mark_as_read" => logged_in(authorization.as_deref(), PRIVATE_MESSAGE),
+    "private_message/report" => logged_in(authorization.as_deref(), PRIVATE_MESSAGE_REPORT),
+    "user/unread_count" => logged_in(authorization.as_deref(), UNREAD_COUNT),
+    "user/mark_all_as_read" => logged_in(authorization.as_deref(), r#"{"replies":[]}"#),
+    "comment/mark_as_read" => logged_in(authorization.as_deref(), REPLY_READ),
+    "comment" => logged_in(authorization.as_deref(), COMMENT),
+    "user/save_user_settings" => logged_in(authorization.as_deref(), r#"{"success":true}"#),
+    "user/logout" => json(200, r#"{"success":true}"#),
+    "post/like" => logged_in(authorization.as_deref(), POST_LIKE),
+    _ => HttpResponse::NotFound().finish(),
+  }
+}
+
+/// `body` for requests carrying the jwt of `USERNAME`, what lemmy says to anyone else
+fn logged_in(authorization: Option<&str>, body: &str) -> HttpResponse {
+  if authorization == Some(format!("Bearer {JWT}").as_str()) {
+    json(200, body)
+  } else {
+    lemmy_error("not_logged_in")
+  }
+}
+
+/// The private messages of `body` that lemmy would list for `query`, only those written by
+/// `creator_id` where one is given
+fn by_creator(query: &str, body: &str) -> String {
+  let Some(creator_id) = query.split('&').find_map(|p| p.strip_prefix("creator_id=")) else {
+    return body.to_string();
+  };
+  let Ok(mut list) = serde_json::from_str::<serde_json::Value>(body) else {
+    return body.to_string();
+  };
+  if let Some(messages) = list
+    .get_mut("private_messages")
+    .and_then(serde_json::Value::as_array_mut)
+  {
+    messages.retain(|m| m["creator"]["id"].to_string() == creator_id);
+  }
+  list.to_string()
+}
+
+fn json(status: u16, body: &str) -> HttpResponse {
+  HttpResponse::build(StatusCode::from_u16(status).unwrap())
+    .content_type("application/json")
+    .body(body.to_string())
+}
+
+// lemmy reports api errors as a 400 with the error type in the body
+fn lemmy_error(error: &str) -> HttpResponse {
+  json(400, &format!(r#"{{"error":"{error}"}}"#))
+}
+
+/// Sets up the reactive runtime and the request `LemmyClient` expects on the server, as if called
+/// while rendering a request carrying the given jwt cookie. Dispose of the runtime when done.
+pub fn request_context(jwt: Option<&str>) -> RuntimeId {
+  let runtime = create_runtime();
+
+  let mut req = actix_web::test::TestRequest::default().app_data(web::Data::new(Client::new()));
+  if let Some(jwt) = jwt {
+    req = req.cookie(actix_web::cookie::Cookie::new("jwt", jwt.to_string()));
+  }
+  provide_context(req.to_http_request());
+
+  runtime
+}
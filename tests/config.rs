@@ -0,0 +1,52 @@
+#![cfg(feature = "ssr")]
+
+use lemmy_api_common::lemmy_db_schema::{ListingType, SortType};
+use lemmy_ui_leptos::config::{AppConfig, LEMMY_UI_LEPTOS_LEMMY_API_VERSION};
+
+#[test]
+fn file_overrides_defaults() {
+  let config = AppConfig::from_toml(
+    r#"
+      [lemmy]
+      host = "lemmy:8536"
+
+      [proxy]
+      trusted_proxies = ["10.0.0.1"]
+
+      [cookie]
+      domain = "example.com"
+
+      [ui]
+      default_theme = "dark"
+      default_sort_type = "New"
+    "#,
+  )
+  .unwrap();
+
+  assert_eq!(config.lemmy.host, "lemmy:8536");
+  assert_eq!(config.lemmy.api_version, LEMMY_UI_LEPTOS_LEMMY_API_VERSION);
+  assert_eq!(
+    config.proxy.trusted_proxies,
+    vec!["10.0.0.1".parse::<std::net::IpAddr>().unwrap()]
+  );
+  assert_eq!(config.proxy.timeout_secs, 300);
+  assert_eq!(config.public.cookie.domain.as_deref(), Some("example.com"));
+  assert_eq!(config.public.cookie.max_age_secs, 604800);
+  assert_eq!(config.public.ui.default_theme, "dark");
+  assert_eq!(config.public.ui.default_sort_type, SortType::New);
+  assert_eq!(config.public.ui.default_listing_type, ListingType::Local);
+}
+
+#[test]
+fn rejects_unreadable_file() {
+  assert!(AppConfig::from_toml("[ui]\nwide_page_limit = \"many\"").is_err());
+}
+
+#[test]
+fn public_config_leaves_out_lemmy() {
+  let mut config = AppConfig::default();
+  config.lemmy.host = "internal-lemmy:8536".into();
+
+  let json = serde_json::to_string(&config.public).unwrap();
+  assert!(!json.contains("internal-lemmy"));
+}
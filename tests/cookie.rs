@@ -0,0 +1,56 @@
+use lemmy_ui_leptos::{config::CookieConfig, cookie::CookieAttributes};
+
+fn config(domain: Option<&str>, secure: Option<bool>) -> CookieConfig {
+  CookieConfig {
+    domain: domain.map(String::from),
+    secure,
+    ..CookieConfig::default()
+  }
+}
+
+#[test]
+fn host_only_by_default() {
+  let attributes = CookieAttributes::new(&config(None, None), "lemmy.example.com:1237", false);
+
+  assert_eq!(attributes.domain, None);
+  assert_eq!(attributes.path, "/");
+  assert!(!attributes.secure);
+}
+
+#[test]
+fn secure_follows_scheme_unless_configured() {
+  assert!(CookieAttributes::new(&config(None, None), "lemmy.example.com", true).secure);
+  assert!(!CookieAttributes::new(&config(None, Some(false)), "lemmy.example.com", true).secure);
+  assert!(CookieAttributes::new(&config(None, Some(true)), "localhost:1237", false).secure);
+}
+
+#[test]
+fn configured_domain_only_within_it() {
+  let config = config(Some(".Example.com"), None);
+
+  assert_eq!(
+    CookieAttributes::new(&config, "example.com", true)
+      .domain
+      .as_deref(),
+    Some("example.com")
+  );
+  assert_eq!(
+    CookieAttributes::new(&config, "lemmy.example.com:443", true)
+      .domain
+      .as_deref(),
+    Some("example.com")
+  );
+  // the browser would drop a cookie for a domain the page is not on
+  assert_eq!(
+    CookieAttributes::new(&config, "localhost:1237", false).domain,
+    None
+  );
+  assert_eq!(
+    CookieAttributes::new(&config, "notexample.com", false).domain,
+    None
+  );
+  assert_eq!(
+    CookieAttributes::new(&config, "[::1]:1237", false).domain,
+    None
+  );
+}
@@ -0,0 +1,182 @@
+#![cfg(feature = "ssr")]
+
+mod common;
+
+use common::{MockLemmy, JWT, PASSWORD, USERNAME};
+use lemmy_api_common::{
+  lemmy_db_schema::newtypes::PostId,
+  person::Login,
+  post::{CreatePostLike, GetPost, GetPosts},
+  LemmyErrorType,
+};
+use lemmy_ui_leptos::{
+  errors::LemmyAppErrorType,
+  lemmy_client::{LemmyClient, PublicFetch, RetryPolicy},
+};
+
+fn client(mock: &MockLemmy) -> LemmyClient {
+  LemmyClient::new(mock.client_options()).with_retry_policy(RetryPolicy::none())
+}
+
+#[actix_web::test]
+async fn decodes_fixtures() {
+  let mock = MockLemmy::start();
+  let runtime = common::request_context(None);
+  let client = client(&mock);
+
+  let site = client.get_site().await.unwrap();
+  assert_eq!(site.site_view.site.name, "Mock Lemmy");
+
+  let posts = client.list_posts(GetPosts::default()).await.unwrap();
+  assert_eq!(posts.posts.len(), 1);
+
+  let post = client
+    .get_post(GetPost {
+      id: Some(PostId(1)),
+      comment_id: None,
+    })
+    .await
+    .unwrap();
+  assert_eq!(post.post_view.post.name, "Hello from the mock backend");
+
+  let requests = mock.requests();
+  assert_eq!(requests.len(), 3);
+  assert!(requests.iter().all(|r| r.method == "GET"));
+  assert!(requests.iter().all(|r| r.authorization.is_none()));
+  assert!(requests.iter().all(|r| r.request_id.is_some()));
+
+  runtime.dispose();
+}
+
+#[actix_web::test]
+async fn maps_lemmy_error_type() {
+  let mock = MockLemmy::start();
+  let runtime = common::request_context(None);
+
+  let error = client(&mock)
+    .get_post(GetPost {
+      id: Some(PostId(999)),
+      comment_id: None,
+    })
+    .await
+    .unwrap_err();
+  assert_eq!(
+    error.error_type,
+    LemmyAppErrorType::ApiError(LemmyErrorType::CouldntFindPost)
+  );
+
+  runtime.dispose();
+}
+
+#[actix_web::test]
+async fn maps_http_status() {
+  let mock = MockLemmy::start();
+  let runtime = common::request_context(None);
+  let client = client(&mock);
+
+  mock.respond_with("site", 503, "");
+  let error = client.get_site().await.unwrap_err();
+  assert_eq!(error.error_type, LemmyAppErrorType::ServiceUnavailable);
+
+  mock.respond_with("post/list", 404, "<html>not here</html>");
+  let error = client.list_posts(GetPosts::default()).await.unwrap_err();
+  assert_eq!(error.error_type, LemmyAppErrorType::NotFound);
+
+  runtime.dispose();
+}
+
+#[actix_web::test]
+async fn rejects_malformed_body() {
+  let mock = MockLemmy::start();
+  let runtime = common::request_context(None);
+  let client = client(&mock);
+
+  mock.respond_with("post/list", 200, r#"{"posts": 5}"#);
+  let error = client.list_posts(GetPosts::default()).await.unwrap_err();
+  assert_eq!(error.error_type, LemmyAppErrorType::InternalServerError);
+
+  mock.respond_with("site", 200, "");
+  let error = client.get_site().await.unwrap_err();
+  assert_eq!(error.error_type, LemmyAppErrorType::InternalServerError);
+
+  runtime.dispose();
+}
+
+#[actix_web::test]
+async fn retries_transient_failures_on_get_only() {
+  let mock = MockLemmy::start();
+  let runtime = common::request_context(Some(JWT));
+  let client = LemmyClient::new(mock.client_options());
+
+  mock.respond_with("site", 503, "");
+  assert!(client.get_site().await.is_err());
+  let requests = mock.requests_to("site");
+  assert_eq!(
+    requests.len(),
+    1 + RetryPolicy::default().max_retries as usize
+  );
+  // every attempt belongs to the same logical request
+  assert!(requests
+    .iter()
+    .all(|r| r.request_id == requests[0].request_id));
+
+  mock.respond_with("post/like", 503, "");
+  let form = CreatePostLike {
+    post_id: PostId(1),
+    score: 1,
+  };
+  assert!(client.like_post(form).await.is_err());
+  assert_eq!(mock.requests_to("post/like").len(), 1);
+
+  runtime.dispose();
+}
+
+#[actix_web::test]
+async fn login_and_authenticated_requests() {
+  let mock = MockLemmy::start();
+
+  let runtime = common::request_context(None);
+  let client = client(&mock);
+
+  let error = client
+    .login(Login {
+      username_or_email: USERNAME.to_string().into(),
+      password: "wrong".to_string().into(),
+      totp_2fa_token: None,
+    })
+    .await
+    .unwrap_err();
+  assert_eq!(
+    error.error_type,
+    LemmyAppErrorType::ApiError(LemmyErrorType::IncorrectLogin)
+  );
+
+  let response = client
+    .login(Login {
+      username_or_email: USERNAME.to_string().into(),
+      password: PASSWORD.to_string().into(),
+      totp_2fa_token: None,
+    })
+    .await
+    .unwrap();
+  assert_eq!(response.jwt.unwrap().into_inner(), JWT);
+  runtime.dispose();
+
+  // the jwt travels as a cookie on later requests, and is sent on to lemmy as a bearer token
+  let runtime = common::request_context(Some(JWT));
+  let response = client
+    .like_post(CreatePostLike {
+      post_id: PostId(1),
+      score: 1,
+    })
+    .await
+    .unwrap();
+  assert_eq!(response.post_view.my_vote, Some(1));
+  assert_eq!(
+    mock.requests_to("post/like")[0].authorization.as_deref(),
+    Some(format!("Bearer {JWT}").as_str())
+  );
+
+  client.logout().await.unwrap();
+  runtime.dispose();
+}
@@ -0,0 +1,44 @@
+use lemmy_api_common::LemmyErrorType;
+use lemmy_ui_leptos::errors::api_error_key;
+use serde_json::{Map, Value};
+use strum::IntoEnumIterator;
+
+fn locale(name: &str) -> Map<String, Value> {
+  let path = format!("{}/locales/{name}.json", env!("CARGO_MANIFEST_DIR"));
+  serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+// fails when a lemmy upgrade brings errors that would only show the generic message
+#[test]
+fn every_lemmy_error_has_a_message() {
+  for name in ["en", "fr"] {
+    let messages = locale(name);
+
+    let missing = LemmyErrorType::iter()
+      .map(|e| api_error_key(&e).unwrap_or_else(|| panic!("{e:?} does not serialize")))
+      .filter(|key| !messages.contains_key(key))
+      .collect::<Vec<_>>();
+
+    assert!(missing.is_empty(), "missing in {name}.json: {missing:?}");
+  }
+}
+
+#[test]
+fn locales_have_the_same_keys() {
+  let en = locale("en");
+  let fr = locale("fr");
+
+  assert!(en.keys().eq(fr.keys()));
+}
+
+#[test]
+fn error_key_follows_lemmy_serialization() {
+  assert_eq!(
+    api_error_key(&LemmyErrorType::CouldntFindPost).as_deref(),
+    Some("api_error_couldnt_find_post")
+  );
+  assert_eq!(
+    api_error_key(&LemmyErrorType::DomainBlocked("example.com".into())).as_deref(),
+    Some("api_error_domain_blocked")
+  );
+}
@@ -0,0 +1,53 @@
+use lemmy_ui_leptos::redirect::local_path;
+
+const HOST: &str = "lemmy.example.com";
+
+#[test]
+fn keeps_local_paths() {
+  assert_eq!(local_path("/", HOST).as_deref(), Some("/"));
+  assert_eq!(
+    local_path("/post/42?sort=New", HOST).as_deref(),
+    Some("/post/42?sort=New")
+  );
+}
+
+#[test]
+fn takes_the_path_of_urls_on_this_host() {
+  assert_eq!(
+    local_path("https://lemmy.example.com/?page=3", HOST).as_deref(),
+    Some("/?page=3")
+  );
+  assert_eq!(
+    local_path("http://LEMMY.example.com/post/42", HOST).as_deref(),
+    Some("/post/42")
+  );
+  assert_eq!(
+    local_path("https://lemmy.example.com", HOST).as_deref(),
+    Some("/")
+  );
+}
+
+#[test]
+fn drops_fragments() {
+  assert_eq!(
+    local_path("/post/42#comment-7", HOST).as_deref(),
+    Some("/post/42")
+  );
+}
+
+#[test]
+fn refuses_other_sites() {
+  for target in [
+    "https://evil.example/",
+    "https://lemmy.example.com.evil.example/",
+    "https://lemmy.example.com@evil.example/",
+    "//evil.example/",
+    "/\\evil.example/",
+    "https://lemmy.example.com//evil.example/",
+    "javascript:alert(1)",
+    "post/42",
+    "/post/42\r\nSet-Cookie: a=b",
+  ] {
+    assert_eq!(local_path(target, HOST), None, "{target}");
+  }
+}
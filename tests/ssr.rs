@@ -0,0 +1,1964 @@
+#![cfg(feature = "ssr")]
+
+mod common;
+
+use actix_web::{
+  body::MessageBody,
+  dev::{Service, ServiceResponse},
+  http::{header, StatusCode},
+  test,
+  App,
+};
+use common::{
+  MockLemmy,
+  EMAIL_TOKEN,
+  JWT,
+  PASSWORD,
+  PRIVATE_MESSAGE,
+  PRIVATE_MESSAGES,
+  SITE,
+  SITE_USER,
+  TOTP_TOKEN,
+  TOTP_USERNAME,
+  USERNAME,
+};
+use lemmy_api_common::LemmyErrorType;
+use lemmy_ui_leptos::{
+  config::AppConfig,
+  errors::{LemmyAppError, LemmyAppErrorType},
+  server,
+};
+use leptos::get_configuration;
+use leptos_actix::generate_route_list;
+
+async fn app(
+  mock: &MockLemmy,
+) -> impl Service<
+  actix_http::Request,
+  Response = ServiceResponse<impl MessageBody>,
+  Error = actix_web::Error,
+> {
+  app_with(mock.config()).await
+}
+
+async fn app_with(
+  config: AppConfig,
+) -> impl Service<
+  actix_http::Request,
+  Response = ServiceResponse<impl MessageBody>,
+  Error = actix_web::Error,
+> {
+  let conf = get_configuration(Some("Cargo.toml")).await.unwrap();
+  let routes = generate_route_list(lemmy_ui_leptos::App);
+  test::init_service(App::new().configure(server::configure(conf.leptos_options, routes, config)))
+    .await
+}
+
+async fn body_text(res: ServiceResponse<impl MessageBody>) -> String {
+  String::from_utf8(test::read_body(res).await.to_vec()).unwrap()
+}
+
+/// The action of the first rendered form posting to a server function whose path contains `name`.
+fn form_action(html: &str, name: &str) -> String {
+  html
+    .split("action=\"")
+    .skip(1)
+    .filter_map(|s| s.split('"').next())
+    .find(|action| action.starts_with("/serverfn/") && action.contains(name))
+    .unwrap_or_else(|| panic!("no form for {name}"))
+    .to_string()
+}
+
+fn location(res: &ServiceResponse<impl MessageBody>) -> String {
+  res
+    .headers()
+    .get(header::LOCATION)
+    .and_then(|l| l.to_str().ok())
+    .unwrap_or_default()
+    .to_string()
+}
+
+// what a browser without javascript sends when submitting an `ActionForm`
+fn form_post(action: &str, form: &[(&str, &str)]) -> test::TestRequest {
+  test::TestRequest::post()
+    .uri(action)
+    .insert_header((header::ACCEPT, "text/html"))
+    .insert_header((header::REFERER, "/"))
+    .set_form(form)
+}
+
+#[actix_web::test]
+async fn renders_home() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+  assert_eq!(res.status(), StatusCode::OK);
+  let html = body_text(res).await;
+
+  assert!(html.contains("Mock Lemmy"));
+  assert!(html.contains("Hello from the mock backend"));
+  assert!(!mock.requests_to("site").is_empty());
+  assert!(!mock.requests_to("post/list").is_empty());
+}
+
+#[actix_web::test]
+async fn pages_leave_out_lemmy_host() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+  let html = body_text(res).await;
+
+  // the browser goes through the proxy, all it is told is the api version
+  assert!(!html.contains(&mock.config().lemmy.host));
+  assert!(html.contains("content=\"v3\""));
+}
+
+#[actix_web::test]
+async fn renders_post_with_comments() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/post/1").to_request()).await;
+  assert_eq!(res.status(), StatusCode::OK);
+  let html = body_text(res).await;
+
+  assert!(html.contains("Hello from the mock backend"));
+  assert!(html.contains("First comment from the mock backend"));
+}
+
+#[actix_web::test]
+async fn renders_missing_post_as_not_found() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/post/999").to_request()).await;
+  assert_eq!(res.status(), StatusCode::NOT_FOUND);
+  let html = body_text(res).await;
+
+  // the apostrophe of "Couldn't" comes back escaped
+  assert!(html.contains("find the post."));
+  assert!(!html.contains("couldnt_find_post"));
+}
+
+#[actix_web::test]
+async fn renders_invalid_post_id_as_not_found() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/post/abc").to_request()).await;
+  assert_eq!(res.status(), StatusCode::NOT_FOUND);
+  let html = body_text(res).await;
+
+  assert!(html.contains("The address of this page is not valid."));
+  assert!(!html.contains("error_type"));
+}
+
+#[actix_web::test]
+async fn renders_malformed_response_as_internal_error() {
+  let mock = MockLemmy::start();
+  mock.respond_with("post", 200, r#"{"post_view": null}"#);
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/post/1").to_request()).await;
+  assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+  let html = body_text(res).await;
+
+  assert!(html.contains("Something went wrong"));
+}
+
+#[actix_web::test]
+async fn renders_unavailable_backend_as_service_unavailable() {
+  let mock = MockLemmy::start();
+  mock.respond_with("post", 503, "");
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/post/1").to_request()).await;
+  assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+  let html = body_text(res).await;
+
+  assert!(html.contains("Temporarily unavailable"));
+}
+
+#[actix_web::test]
+async fn renders_unknown_route() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get().uri("/nowhere/at/all").to_request(),
+  )
+  .await;
+  assert_eq!(res.status(), StatusCode::NOT_FOUND);
+  assert!(body_text(res).await.contains("Page not found"));
+}
+
+#[actix_web::test]
+async fn renders_community() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/c/main").to_request()).await;
+  assert_eq!(res.status(), StatusCode::OK);
+  let html = body_text(res).await;
+
+  assert!(html.contains("The community of the mock backend"));
+  assert!(html.contains("href=\"/u/lemmy\""));
+  // only the posts of that community
+  let posts = mock.requests_to("post/list");
+  assert!(posts
+    .iter()
+    .all(|r| r.query.contains("community_name=main")));
+}
+
+#[actix_web::test]
+async fn renders_missing_community_as_not_found() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/c/nope").to_request()).await;
+  assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn subscribe_returns_to_the_community() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/c/main")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  let action = form_action(&body_text(res).await, "follow_community");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("community_id", "2"),
+        ("follow", "true"),
+        ("return_to", "/c/main"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert!(res.status().is_redirection());
+  assert_eq!(location(&res), "/c/main");
+
+  let follows = mock.requests_to("community/follow");
+  assert_eq!(follows.len(), 1);
+  assert!(follows[0].body.contains("\"follow\":true"));
+}
+
+#[actix_web::test]
+async fn renders_communities() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/communities?list=%22Local%22&sort=%22New%22&page=2")
+      .to_request(),
+  )
+  .await;
+  assert_eq!(res.status(), StatusCode::OK);
+  let html = body_text(res).await;
+
+  assert!(html.contains("href=\"/c/main\""));
+  assert!(html.contains("follow_community"));
+  assert!(html.contains("Prev"));
+
+  let lists = mock.requests_to("community/list");
+  assert_eq!(lists.len(), 1);
+  assert!(lists[0].query.contains("type_=Local"));
+  assert!(lists[0].query.contains("sort=New"));
+  assert!(lists[0].query.contains("show_nsfw=false"));
+  assert!(lists[0].query.contains("page=2"));
+}
+
+#[actix_web::test]
+async fn searches_communities_by_name() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/communities?q=main")
+      .to_request(),
+  )
+  .await;
+  assert_eq!(res.status(), StatusCode::OK);
+  assert!(body_text(res).await.contains("href=\"/c/main\""));
+
+  let searches = mock.requests_to("search");
+  assert_eq!(searches.len(), 1);
+  assert!(searches[0].query.contains("q=main"));
+  assert!(searches[0].query.contains("type_=Communities"));
+}
+
+#[actix_web::test]
+async fn renders_profile() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/u/alice").to_request()).await;
+  assert_eq!(res.status(), StatusCode::OK);
+  let html = body_text(res).await;
+
+  assert!(html.contains("Hello, I am the other user of the mock backend."));
+  // the community alice moderates
+  assert!(html.contains("href=\"/c/main\""));
+  // nothing to do for visitors
+  assert!(!html.contains("create_private_message"));
+  assert!(!html.contains("ban_person"));
+
+  let details = mock.requests_to("user");
+  assert!(details[0].query.contains("username=alice"));
+  assert!(details[0].query.contains("sort=New"));
+}
+
+#[actix_web::test]
+async fn renders_missing_person_as_not_found() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/u/nobody").to_request()).await;
+  assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn admin_bans_from_profile() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/u/alice")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  let html = body_text(res).await;
+  assert!(html.contains("href=\"/create_private_message/3\""));
+  assert!(html.contains("purge_person"));
+  let action = form_action(&html, "ban_person");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("person_id", "3"),
+        ("ban", "true"),
+        ("reason", "spam"),
+        ("days", "7"),
+        ("return_to", "/u/alice"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert!(res.status().is_redirection());
+  assert_eq!(location(&res), "/u/alice");
+
+  let bans = mock.requests_to("user/ban");
+  assert_eq!(bans.len(), 1);
+  let form: serde_json::Value = serde_json::from_str(&bans[0].body).unwrap();
+  assert_eq!(form["ban"], true);
+  assert_eq!(form["reason"], "spam");
+  assert!(form["expires"].is_i64());
+}
+
+#[actix_web::test]
+async fn purge_leaves_the_profile() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/u/alice")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  let action = form_action(&body_text(res).await, "purge_person");
+
+  let res = test::call_service(
+    &app,
+    form_post(&action, &[("person_id", "3"), ("return_to", "/u/alice")])
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/");
+  assert_eq!(mock.requests_to("admin/purge/person").len(), 1);
+}
+
+#[actix_web::test]
+async fn renders_inbox() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/inbox")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(res.status(), StatusCode::OK);
+  let html = body_text(res).await;
+
+  assert!(html.contains("A reply from alice"));
+  assert!(html.contains("a mention from alice"));
+  assert!(html.contains("A private message from alice"));
+  // newest first
+  assert!(html.find("message-1").unwrap() < html.find("reply-1").unwrap());
+  // the unread count in the nav
+  assert!(!mock.requests_to("user/unread_count").is_empty());
+
+  let replies = mock.requests_to("user/replies");
+  assert!(replies[0].query.contains("unread_only=true"));
+}
+
+#[actix_web::test]
+async fn renders_inbox_as_forbidden_when_logged_out() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/inbox").to_request()).await;
+  assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn marks_inbox_items_as_read() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/inbox?view=replies")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  let html = body_text(res).await;
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &form_action(&html, "mark_reply_as_read"),
+      &[
+        ("comment_reply_id", "1"),
+        ("read", "true"),
+        ("return_to", "/inbox?view=replies"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/inbox?view=replies");
+  assert_eq!(mock.requests_to("comment/mark_as_read").len(), 1);
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &form_action(&html, "mark_all_as_read"),
+      &[("return_to", "/inbox")],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/inbox");
+  assert_eq!(mock.requests_to("user/mark_all_as_read").len(), 1);
+}
+
+#[actix_web::test]
+async fn replies_from_the_inbox() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/inbox")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  let action = form_action(&body_text(res).await, "create_comment");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("post_id", "1"),
+        ("parent_id", "2"),
+        ("content", "Thanks alice"),
+        ("return_to", "/inbox"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/inbox#comment-2");
+
+  let comments = mock.requests_to("comment");
+  assert_eq!(comments.len(), 1);
+  let form: serde_json::Value = serde_json::from_str(&comments[0].body).unwrap();
+  assert_eq!(form["parent_id"], 2);
+  assert_eq!(form["content"], "Thanks alice");
+
+  // an empty reply never reaches lemmy
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("post_id", "1"),
+        ("parent_id", "2"),
+        ("content", " "),
+        ("return_to", "/inbox"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/inbox#comment-2");
+  assert!(res.headers().get(header::SET_COOKIE).is_some());
+  assert_eq!(mock.requests_to("comment").len(), 1);
+}
+
+#[actix_web::test]
+async fn renders_conversation() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/messages/3")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(res.status(), StatusCode::OK);
+  let html = body_text(res).await;
+
+  assert!(html.contains("href=\"/u/alice\""));
+  assert!(html.contains("A private message from alice"));
+  assert!(html.contains("id=\"message-1\""));
+  // a received message is reported, not edited
+  assert!(html.contains("report_private_message"));
+  assert!(!html.contains("edit_private_message"));
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/messages")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(res.status(), StatusCode::OK);
+  assert!(body_text(res).await.contains("href=\"/messages/3\""));
+}
+
+#[actix_web::test]
+async fn renders_conversation_as_forbidden_when_logged_out() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get().uri("/messages/3").to_request(),
+  )
+  .await;
+  assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn sends_private_message() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/messages/3")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  let action = form_action(&body_text(res).await, "create_private_message");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("recipient_id", "3"),
+        ("content", "A private message to alice"),
+        ("return_to", "/messages/3"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/messages/3#message-2");
+
+  let messages = mock.requests_to("private_message");
+  assert_eq!(messages.len(), 1);
+  let form: serde_json::Value = serde_json::from_str(&messages[0].body).unwrap();
+  assert_eq!(form["recipient_id"], 3);
+  assert_eq!(form["content"], "A private message to alice");
+
+  // an empty message never reaches lemmy
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("recipient_id", "3"),
+        ("content", ""),
+        ("return_to", "/messages/3"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/messages/3");
+  assert!(res.headers().get(header::SET_COOKIE).is_some());
+  assert_eq!(mock.requests_to("private_message").len(), 1);
+}
+
+#[actix_web::test]
+async fn pages_conversation_by_creator() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  // a full page from alice, so there are probably older ones
+  let mut list: serde_json::Value = serde_json::from_str(PRIVATE_MESSAGES).unwrap();
+  let message = list["private_messages"][0].clone();
+  list["private_messages"] = (1..=50)
+    .map(|id| {
+      let mut message = message.clone();
+      message["private_message"]["id"] = id.into();
+      message
+    })
+    .collect();
+  mock.respond_with("private_message/list", 200, &list.to_string());
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/messages/3")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(res.status(), StatusCode::OK);
+  let html = body_text(res).await;
+  assert!(html.contains("id=\"message-50\""));
+  assert!(html.contains("pages=2"));
+
+  // what alice wrote and what we wrote, as lemmy only filters by creator
+  let queries = mock
+    .requests_to("private_message/list")
+    .into_iter()
+    .map(|r| r.query)
+    .collect::<Vec<_>>();
+  assert!(queries.iter().any(|q| q.contains("creator_id=3")));
+  assert!(queries.iter().any(|q| q.contains("creator_id=2")));
+}
+
+#[actix_web::test]
+async fn edits_deletes_and_reports_private_messages() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  // the conversation with a message of our own in it
+  let mut list: serde_json::Value = serde_json::from_str(PRIVATE_MESSAGES).unwrap();
+  let sent: serde_json::Value = serde_json::from_str(PRIVATE_MESSAGE).unwrap();
+  list["private_messages"]
+    .as_array_mut()
+    .unwrap()
+    .push(sent["private_message_view"].clone());
+  mock.respond_with("private_message/list", 200, &list.to_string());
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/messages/3")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  let html = body_text(res).await;
+  assert!(html.contains("A private message to alice"));
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &form_action(&html, "edit_private_message"),
+      &[
+        ("private_message_id", "2"),
+        ("content", "An edited message"),
+        ("return_to", "/messages/3"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/messages/3#message-2");
+  let edits = mock.requests_to("private_message");
+  assert_eq!(edits[0].method, "PUT");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &form_action(&html, "delete_private_message"),
+      &[
+        ("private_message_id", "2"),
+        ("deleted", "true"),
+        ("return_to", "/messages/3"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/messages/3#message-2");
+  assert_eq!(mock.requests_to("private_message/delete").len(), 1);
+
+  // a report needs a reason
+  let res = test::call_service(
+    &app,
+    form_post(
+      &form_action(&html, "report_private_message"),
+      &[
+        ("private_message_id", "1"),
+        ("reason", ""),
+        ("return_to", "/messages/3"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/messages/3#message-1");
+  assert!(mock.requests_to("private_message/report").is_empty());
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &form_action(&html, "report_private_message"),
+      &[
+        ("private_message_id", "1"),
+        ("reason", "Spam"),
+        ("return_to", "/messages/3"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/messages/3#message-1");
+  assert_eq!(mock.requests_to("private_message/report").len(), 1);
+}
+
+// the logged in site, with the theme and interface language saved in the account changed
+fn site_with_ui(theme: &str, interface_language: &str) -> String {
+  let mut site: serde_json::Value = serde_json::from_str(SITE_USER).unwrap();
+  let local_user = &mut site["my_user"]["local_user_view"]["local_user"];
+  local_user["theme"] = theme.into();
+  local_user["interface_language"] = interface_language.into();
+  site.to_string()
+}
+
+#[actix_web::test]
+async fn renders_settings() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/settings")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(res.status(), StatusCode::OK);
+  let html = body_text(res).await;
+
+  assert!(html.contains("value=\"lemmy@lemmy.test\""));
+  assert!(html.contains("name=\"discussion_languages[0]\""));
+  form_action(&html, "save_user_settings");
+  form_action(&html, "change_email");
+  form_action(&html, "change_password");
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/settings").to_request()).await;
+  assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn saves_settings() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/settings")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  let action = form_action(&body_text(res).await, "save_user_settings");
+
+  // what lemmy answers once the settings are saved
+  mock.respond_with("site", 200, &site_with_ui("dark", "fr"));
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("display_name", "Lemmy"),
+        ("bio", ""),
+        ("avatar", ""),
+        ("banner", ""),
+        ("show_nsfw", "true"),
+        ("default_listing_type", "\"Subscribed\""),
+        ("default_sort_type", "\"New\""),
+        ("theme", "dark"),
+        ("interface_language", "fr"),
+        ("discussion_languages[0]", "0"),
+        ("return_to", "/settings"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/settings");
+
+  let saves = mock.requests_to("user/save_user_settings");
+  assert_eq!(saves.len(), 1);
+  let form: serde_json::Value = serde_json::from_str(&saves[0].body).unwrap();
+  assert_eq!(form["display_name"], "Lemmy");
+  assert_eq!(form["show_nsfw"], true);
+  // left unchecked
+  assert_eq!(form["show_read_posts"], false);
+  assert_eq!(form["default_listing_type"], "Subscribed");
+  assert_eq!(form["default_sort_type"], "New");
+  assert_eq!(form["discussion_languages"], serde_json::json!([0]));
+
+  // the page renders with what was saved from now on
+  let cookies: Vec<_> = res.response().cookies().collect();
+  assert!(cookies
+    .iter()
+    .any(|c| c.name() == "theme" && c.value() == "dark"));
+  assert!(cookies
+    .iter()
+    .any(|c| c.name() == "i18n_pref_locale" && c.value() == "fr"));
+}
+
+#[actix_web::test]
+async fn saving_browser_default_drops_ui_cookies() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/settings")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  let action = form_action(&body_text(res).await, "save_user_settings");
+
+  mock.respond_with("site", 200, &site_with_ui("browser", "browser"));
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("display_name", "Lemmy"),
+        ("bio", ""),
+        ("avatar", ""),
+        ("banner", ""),
+        ("default_listing_type", "\"All\""),
+        ("default_sort_type", "\"Active\""),
+        ("theme", "browser"),
+        ("interface_language", "browser"),
+        ("return_to", "/settings"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .cookie(actix_web::cookie::Cookie::new("theme", "dark"))
+    .cookie(actix_web::cookie::Cookie::new("i18n_pref_locale", "fr"))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/settings");
+
+  // expired, so that the browser's own preferences apply again
+  let cookies: Vec<_> = res.response().cookies().collect();
+  for name in ["theme", "i18n_pref_locale"] {
+    assert!(cookies
+      .iter()
+      .any(|c| c.name() == name && c.value().is_empty()));
+  }
+}
+
+#[actix_web::test]
+async fn changes_email() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/settings")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  let action = form_action(&body_text(res).await, "change_email");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[("email", " new@lemmy.test "), ("return_to", "/settings")],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/settings");
+
+  // only the email, the other settings stay as they are
+  let saves = mock.requests_to("user/save_user_settings");
+  assert_eq!(saves.len(), 1);
+  let form: serde_json::Value = serde_json::from_str(&saves[0].body).unwrap();
+  assert_eq!(form["email"], "new@lemmy.test");
+  assert!(form["display_name"].is_null());
+  assert!(form["show_nsfw"].is_null());
+}
+
+#[actix_web::test]
+async fn changes_password() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/settings")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  let action = form_action(&body_text(res).await, "change_password");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("old_password", "wrong"),
+        ("new_password", "lemmylemmy"),
+        ("new_password_verify", "lemmylemmy"),
+        ("return_to", "/settings"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/settings");
+  assert!(res.response().cookies().any(|c| c.name() == "flash"));
+  assert!(res.response().cookies().all(|c| c.name() != "jwt"));
+
+  // checked before lemmy is asked
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("old_password", PASSWORD),
+        ("new_password", "lemmylemmy"),
+        ("new_password_verify", "lemmylemmx"),
+        ("return_to", "/settings"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/settings");
+  assert_eq!(mock.requests_to("user/change_password").len(), 1);
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("old_password", PASSWORD),
+        ("new_password", "lemmylemmy"),
+        ("new_password_verify", "lemmylemmy"),
+        ("return_to", "/settings"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/settings");
+  // lemmy ended the old session and handed out a new one
+  let jwt = res
+    .response()
+    .cookies()
+    .find(|c| c.name() == "jwt")
+    .expect("the new session should replace the old one");
+  assert_eq!(jwt.value(), JWT);
+  assert_eq!(mock.requests_to("user/change_password").len(), 2);
+}
+
+#[actix_web::test]
+async fn enables_totp() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/settings?totp=setup")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  let html = body_text(res).await;
+  // no secret until asked for, not even on the setup page
+  assert!(mock.requests_to("user/totp/generate").is_empty());
+  let generate = form_action(&html, "generate_totp_secret");
+
+  let res = test::call_service(
+    &app,
+    form_post(&generate, &[("return_to", "/settings")])
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/settings?totp=setup");
+  assert_eq!(mock.requests_to("user/totp/generate").len(), 1);
+  let secret = res
+    .response()
+    .cookies()
+    .find(|c| c.name() == "totp_secret")
+    .expect("the secret should be kept for the setup")
+    .into_owned();
+  // only this server reads it
+  assert!(!secret.value().contains("JBSWY3DPEHPK3PXP"));
+
+  let setup_page = || {
+    test::TestRequest::get()
+      .uri("/settings?totp=setup")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .cookie(secret.clone())
+      .to_request()
+  };
+
+  let html = body_text(test::call_service(&app, setup_page()).await).await;
+  // drawn here, the top left finder pattern starting after the quiet zone
+  assert!(html.contains("viewBox=\"0 0 "));
+  assert!(html.contains("M4 4h1v1h-1z"));
+  assert!(html.contains("secret=JBSWY3DPEHPK3PXP"));
+  let action = form_action(&html, "update_totp");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("totp_token", "000000"),
+        ("enabled", "true"),
+        ("return_to", "/settings?totp=setup"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .cookie(secret.clone())
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/settings?totp=setup");
+  assert!(res.response().cookies().any(|c| c.name() == "flash"));
+
+  // trying again is against the same secret
+  let html = body_text(test::call_service(&app, setup_page()).await).await;
+  assert!(html.contains("secret=JBSWY3DPEHPK3PXP"));
+  assert_eq!(mock.requests_to("user/totp/generate").len(), 1);
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("totp_token", TOTP_TOKEN),
+        ("enabled", "true"),
+        ("return_to", "/settings?totp=setup"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .cookie(secret.clone())
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/settings");
+  assert!(res
+    .response()
+    .cookies()
+    .any(|c| c.name() == "totp_secret" && c.value().is_empty()));
+
+  let updates = mock.requests_to("user/totp/update");
+  assert_eq!(updates.len(), 2);
+  let form: serde_json::Value = serde_json::from_str(&updates[1].body).unwrap();
+  assert_eq!(form["enabled"], true);
+}
+
+#[actix_web::test]
+async fn disables_totp() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let mut site: serde_json::Value = serde_json::from_str(SITE_USER).unwrap();
+  site["my_user"]["local_user_view"]["local_user"]["totp_2fa_enabled"] = true.into();
+  mock.respond_with("site", 200, &site.to_string());
+
+  // nothing to set up while it is on
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/settings?totp=setup")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  let html = body_text(res).await;
+  assert!(mock.requests_to("user/totp/generate").is_empty());
+  assert!(html.contains("Disable two-factor authentication"));
+  let action = form_action(&html, "update_totp");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("totp_token", TOTP_TOKEN),
+        ("enabled", "false"),
+        ("return_to", "/settings"),
+      ],
+    )
+    .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/settings");
+
+  let updates = mock.requests_to("user/totp/update");
+  let form: serde_json::Value = serde_json::from_str(&updates[0].body).unwrap();
+  assert_eq!(form["enabled"], false);
+  assert_eq!(form["totp_token"], TOTP_TOKEN);
+}
+
+// the logged out site, with some of its registration settings changed
+fn site_with_registration(changes: serde_json::Value) -> String {
+  let mut site: serde_json::Value = serde_json::from_str(SITE).unwrap();
+  let local_site = site["site_view"]["local_site"].as_object_mut().unwrap();
+  for (key, value) in changes.as_object().unwrap() {
+    local_site.insert(key.clone(), value.clone());
+  }
+  site.to_string()
+}
+
+#[actix_web::test]
+async fn signup_logs_in_right_away() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/signup").to_request()).await;
+  assert_eq!(res.status(), StatusCode::OK);
+  let action = form_action(&body_text(res).await, "register");
+
+  // checked before lemmy is asked
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("username", "newuser"),
+        ("password", "lemmylemmy"),
+        ("password_verify", "lemmylemmx"),
+        ("email", ""),
+      ],
+    )
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/signup");
+  assert!(mock.requests_to("user/register").is_empty());
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("username", "newuser"),
+        ("password", "lemmylemmy"),
+        ("password_verify", "lemmylemmy"),
+        ("email", ""),
+        ("honeypot", ""),
+      ],
+    )
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/");
+  let jwt = res
+    .response()
+    .cookies()
+    .find(|c| c.name() == "jwt")
+    .expect("signup should set the jwt cookie");
+  assert_eq!(jwt.value(), JWT);
+  assert_eq!(jwt.http_only(), Some(true));
+
+  let registrations = mock.requests_to("user/register");
+  assert_eq!(registrations.len(), 1);
+  let form: serde_json::Value = serde_json::from_str(&registrations[0].body).unwrap();
+  assert_eq!(form["username"], "newuser");
+  assert!(form["email"].is_null());
+  assert!(form["honeypot"].is_null());
+}
+
+#[actix_web::test]
+async fn signup_with_application_and_captcha() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  mock.respond_with(
+    "site",
+    200,
+    &site_with_registration(serde_json::json!({
+      "registration_mode": "RequireApplication",
+      "application_question": "Why do you want to join?",
+      "captcha_enabled": true,
+      "require_email_verification": true,
+    })),
+  );
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/signup").to_request()).await;
+  let html = body_text(res).await;
+  assert!(html.contains("Why do you want to join?"));
+  assert!(html.contains("data:image/png;base64,aW1hZ2U="));
+  assert!(html.contains("data:audio/wav;base64,YXVkaW8="));
+  assert!(html.contains("value=\"captcha-uuid\""));
+  let action = form_action(&html, "register");
+
+  // an application needs an answer
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("username", "applicant"),
+        ("password", "lemmylemmy"),
+        ("password_verify", "lemmylemmy"),
+        ("email", "applicant@lemmy.test"),
+        ("answer", ""),
+        ("captcha_uuid", "captcha-uuid"),
+        ("captcha_answer", "abcde"),
+      ],
+    )
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/signup");
+  assert!(res.headers().get(header::SET_COOKIE).is_some());
+  assert!(mock.requests_to("user/register").is_empty());
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("username", "applicant"),
+        ("password", "lemmylemmy"),
+        ("password_verify", "lemmylemmy"),
+        ("email", "applicant@lemmy.test"),
+        ("answer", "To talk about lemmy"),
+        ("captcha_uuid", "captcha-uuid"),
+        ("captcha_answer", "abcde"),
+      ],
+    )
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/signup?verify_email=true&application=true");
+  assert!(res.response().cookies().all(|c| c.name() != "jwt"));
+
+  let registrations = mock.requests_to("user/register");
+  let form: serde_json::Value = serde_json::from_str(&registrations[0].body).unwrap();
+  assert_eq!(form["answer"], "To talk about lemmy");
+  assert_eq!(form["captcha_uuid"], "captcha-uuid");
+  assert_eq!(form["captcha_answer"], "abcde");
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/signup?verify_email=true&application=true")
+      .to_request(),
+  )
+  .await;
+  let html = body_text(res).await;
+  assert!(html.contains("verify your address"));
+  assert!(html.contains("waiting for an admin"));
+}
+
+#[actix_web::test]
+async fn signup_returns_lemmy_errors_to_the_form() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/signup").to_request()).await;
+  let action = form_action(&body_text(res).await, "register");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("username", USERNAME),
+        ("password", "lemmylemmy"),
+        ("password_verify", "lemmylemmy"),
+        ("email", ""),
+      ],
+    )
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/signup");
+  let flash = res
+    .response()
+    .cookies()
+    .find(|c| c.name() == "flash")
+    .expect("the error should come back in a flash")
+    .into_owned();
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/signup")
+      .cookie(flash)
+      .to_request(),
+  )
+  .await;
+  // the username is marked
+  assert!(body_text(res).await.contains("input-error"));
+}
+
+#[actix_web::test]
+async fn renders_closed_signup() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  mock.respond_with(
+    "site",
+    200,
+    &site_with_registration(serde_json::json!({ "registration_mode": "Closed" })),
+  );
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/signup").to_request()).await;
+  let html = body_text(res).await;
+  assert!(html.contains("Registration is closed on this site."));
+  assert!(!html.contains("/serverfn/register"));
+}
+
+#[actix_web::test]
+async fn requests_password_reset() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/login").to_request()).await;
+  assert!(body_text(res).await.contains("href=\"/password_reset\""));
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get().uri("/password_reset").to_request(),
+  )
+  .await;
+  assert_eq!(res.status(), StatusCode::OK);
+  let action = form_action(&body_text(res).await, "password_reset");
+
+  let res = test::call_service(&app, form_post(&action, &[("email", "")]).to_request()).await;
+  assert_eq!(location(&res), "/password_reset");
+  assert!(mock.requests_to("user/password_reset").is_empty());
+
+  let res = test::call_service(
+    &app,
+    form_post(&action, &[("email", "lemmy@lemmy.test")]).to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/password_reset?sent=true");
+  let resets = mock.requests_to("user/password_reset");
+  let form: serde_json::Value = serde_json::from_str(&resets[0].body).unwrap();
+  assert_eq!(form["email"], "lemmy@lemmy.test");
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/password_reset?sent=true")
+      .to_request(),
+  )
+  .await;
+  assert!(body_text(res)
+    .await
+    .contains("an email with a link to reset its password"));
+}
+
+#[actix_web::test]
+async fn changes_password_after_reset() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let page = format!("/password_change/{EMAIL_TOKEN}");
+  let res = test::call_service(&app, test::TestRequest::get().uri(&page).to_request()).await;
+  assert_eq!(res.status(), StatusCode::OK);
+  let html = body_text(res).await;
+  assert!(html.contains(&format!("value=\"{EMAIL_TOKEN}\"")));
+  let action = form_action(&html, "password_change");
+
+  // back to the page of the link
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("token", EMAIL_TOKEN),
+        ("password", "lemmylemmy"),
+        ("password_verify", "lemmylemmx"),
+        ("return_to", &page),
+      ],
+    )
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), page);
+  assert!(mock.requests_to("user/password_change").is_empty());
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("token", EMAIL_TOKEN),
+        ("password", "lemmylemmy"),
+        ("password_verify", "lemmylemmy"),
+        ("return_to", &page),
+      ],
+    )
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/login?password_changed=true");
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/login?password_changed=true")
+      .to_request(),
+  )
+  .await;
+  assert!(body_text(res).await.contains("Your password was changed."));
+}
+
+#[actix_web::test]
+async fn verifies_email() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let page = format!("/verify_email/{EMAIL_TOKEN}");
+  let res = test::call_service(&app, test::TestRequest::get().uri(&page).to_request()).await;
+  assert_eq!(res.status(), StatusCode::OK);
+  let action = form_action(&body_text(res).await, "verify_email");
+  // opening the link alone verifies nothing
+  assert!(mock.requests_to("user/verify_email").is_empty());
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[("token", "expired"), ("return_to", "/verify_email/expired")],
+    )
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/verify_email/expired");
+  assert!(res.response().cookies().any(|c| c.name() == "flash"));
+
+  let res = test::call_service(
+    &app,
+    form_post(&action, &[("token", EMAIL_TOKEN), ("return_to", &page)]).to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/login?email_verified=true");
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/login?email_verified=true")
+      .to_request(),
+  )
+  .await;
+  assert!(body_text(res)
+    .await
+    .contains("Your email address is verified."));
+}
+
+#[actix_web::test]
+async fn login_sets_jwt_cookie() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/login").to_request()).await;
+  let action = form_action(&body_text(res).await, "login");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[("username_or_email", USERNAME), ("password", PASSWORD)],
+    )
+    .to_request(),
+  )
+  .await;
+
+  assert!(res.status().is_redirection());
+  assert_eq!(location(&res), "/");
+  let jwt = res
+    .response()
+    .cookies()
+    .find(|c| c.name() == "jwt")
+    .expect("login should set the jwt cookie");
+  assert_eq!(jwt.value(), JWT);
+  // out of reach of scripts, and only sent over https when the request came in over https
+  assert_eq!(jwt.http_only(), Some(true));
+  assert_ne!(jwt.secure(), Some(true));
+}
+
+#[actix_web::test]
+async fn login_asks_for_totp() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/login").to_request()).await;
+  let html = body_text(res).await;
+  assert!(!html.contains("name=\"totp_2fa_token\""));
+  let action = form_action(&html, "login");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[("username_or_email", TOTP_USERNAME), ("password", PASSWORD)],
+    )
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/login");
+  assert!(res.response().cookies().all(|c| c.name() != "jwt"));
+  let flash = res
+    .response()
+    .cookies()
+    .find(|c| c.name() == "flash")
+    .expect("lemmy asking for a code should be flashed")
+    .into_owned();
+
+  // the second step
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/login")
+      .cookie(flash)
+      .to_request(),
+  )
+  .await;
+  assert!(body_text(res).await.contains("name=\"totp_2fa_token\""));
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("username_or_email", TOTP_USERNAME),
+        ("password", PASSWORD),
+        ("totp_2fa_token", TOTP_TOKEN),
+      ],
+    )
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/");
+  assert!(res.response().cookies().any(|c| c.name() == "jwt"));
+
+  let logins = mock.requests_to("user/login");
+  let form: serde_json::Value = serde_json::from_str(&logins[1].body).unwrap();
+  assert_eq!(form["totp_2fa_token"], TOTP_TOKEN);
+}
+
+#[actix_web::test]
+async fn login_takes_theme_and_language_from_account() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/login").to_request()).await;
+  let action = form_action(&body_text(res).await, "login");
+
+  mock.respond_with("site", 200, &site_with_ui("retro", "fr"));
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[("username_or_email", USERNAME), ("password", PASSWORD)],
+    )
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/");
+
+  // asked with the token just handed out, which the request itself does not carry
+  let sites = mock.requests_to("site");
+  assert_eq!(
+    sites.last().unwrap().authorization.as_deref(),
+    Some(format!("Bearer {JWT}").as_str())
+  );
+
+  let cookies: Vec<_> = res.response().cookies().collect();
+  assert!(cookies
+    .iter()
+    .any(|c| c.name() == "theme" && c.value() == "retro"));
+  assert!(cookies
+    .iter()
+    .any(|c| c.name() == "i18n_pref_locale" && c.value() == "fr"));
+}
+
+#[actix_web::test]
+async fn login_behind_https_proxy_sets_secure_cookie() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/login").to_request()).await;
+  let action = form_action(&body_text(res).await, "login");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[("username_or_email", USERNAME), ("password", PASSWORD)],
+    )
+    .insert_header(("X-Forwarded-Proto", "https"))
+    .insert_header(("X-Forwarded-Host", "lemmy.example.com"))
+    .to_request(),
+  )
+  .await;
+
+  let jwt = res
+    .response()
+    .cookies()
+    .find(|c| c.name() == "jwt")
+    .expect("login should set the jwt cookie");
+  assert_eq!(jwt.secure(), Some(true));
+  assert_eq!(jwt.domain(), None);
+  assert_eq!(jwt.path(), Some("/"));
+}
+
+#[actix_web::test]
+async fn login_with_wrong_password_returns_to_form() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/login").to_request()).await;
+  let action = form_action(&body_text(res).await, "login");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[("username_or_email", USERNAME), ("password", "wrong")],
+    )
+    .to_request(),
+  )
+  .await;
+
+  assert!(res.status().is_redirection());
+  // the error goes along in a cookie rather than in the url
+  assert_eq!(location(&res), "/login");
+  assert!(res.response().cookies().all(|c| c.name() != "jwt"));
+  let flash = res
+    .response()
+    .cookies()
+    .find(|c| c.name() == "flash")
+    .expect("the error should be flashed")
+    .into_owned();
+  assert_eq!(flash.http_only(), Some(true));
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/login")
+      .cookie(flash.clone())
+      .to_request(),
+  )
+  .await;
+  // shown once, then gone
+  let removal = res
+    .response()
+    .cookies()
+    .find(|c| c.name() == "flash")
+    .expect("the flash should be removed")
+    .into_owned();
+  assert_eq!(removal.value(), "");
+  assert!(body_text(res)
+    .await
+    .contains("did not match an account in this instance"));
+}
+
+#[actix_web::test]
+async fn login_called_from_the_app_returns_the_error() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/login").to_request()).await;
+  let action = form_action(&body_text(res).await, "login");
+
+  // as the hydrated app calls it, asking for anything but html
+  let res = test::call_service(
+    &app,
+    test::TestRequest::post()
+      .uri(&action)
+      .insert_header((header::ACCEPT, "application/json"))
+      .set_form([("username_or_email", USERNAME), ("password", "wrong")])
+      .to_request(),
+  )
+  .await;
+
+  assert!(!res.status().is_redirection());
+  assert!(res.response().cookies().all(|c| c.name() != "flash"));
+  let body = body_text(res).await;
+  let error = body
+    .split_once('|')
+    .map_or(body.as_str(), |(_, e)| e)
+    .parse::<LemmyAppError>()
+    .unwrap();
+  assert_eq!(
+    error.error_type,
+    LemmyAppErrorType::ApiError(LemmyErrorType::IncorrectLogin)
+  );
+}
+
+#[actix_web::test]
+async fn ignores_forged_flash() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let forged = serde_json::json!({ "error_type": { "error": "unknown" }, "content": "" });
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/login")
+      .cookie(actix_web::cookie::Cookie::new("flash", forged.to_string()))
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(res.status(), StatusCode::OK);
+  assert!(!body_text(res).await.contains("an unknown error occurred"));
+}
+
+#[actix_web::test]
+async fn vote_sends_jwt_cookie_as_bearer_token() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  let action = form_action(&body_text(res).await, "vote_post");
+
+  let res = test::call_service(
+    &app,
+    form_post(&action, &[("post_id", "1"), ("score", "1")])
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .to_request(),
+  )
+  .await;
+  assert!(res.status().is_redirection());
+  // back to the page the vote came from, at the post
+  assert_eq!(location(&res), "/#post-1");
+
+  let votes = mock.requests_to("post/like");
+  assert_eq!(votes.len(), 1);
+  assert_eq!(
+    votes[0].authorization.as_deref(),
+    Some(format!("Bearer {JWT}").as_str())
+  );
+}
+
+#[actix_web::test]
+async fn vote_returns_to_the_given_page() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+  let action = form_action(&body_text(res).await, "vote_post");
+
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[("post_id", "1"), ("score", "1"), ("return_to", "/?page=3")],
+    )
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/?page=3#post-1");
+
+  // but never to another site, which falls back to the referer
+  let res = test::call_service(
+    &app,
+    form_post(
+      &action,
+      &[
+        ("post_id", "1"),
+        ("score", "1"),
+        ("return_to", "//evil.example/"),
+      ],
+    )
+    .insert_header((header::REFERER, "http://localhost/post/1"))
+    .insert_header((header::HOST, "localhost"))
+    .to_request(),
+  )
+  .await;
+  assert_eq!(location(&res), "/post/1#post-1");
+}
+
+#[actix_web::test]
+async fn proxies_api_requests() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::get()
+      .uri("/api/v3/post?id=999")
+      .insert_header((header::AUTHORIZATION, format!("Bearer {JWT}")))
+      .to_request(),
+  )
+  .await;
+
+  assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+  assert!(body_text(res).await.contains("couldnt_find_post"));
+
+  let requests = mock.requests_to("post");
+  assert_eq!(requests[0].query, "id=999");
+  assert_eq!(
+    requests[0].authorization.as_deref(),
+    Some(format!("Bearer {JWT}").as_str())
+  );
+}
+
+#[actix_web::test]
+async fn proxy_sends_jwt_cookie_as_bearer_token() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  let res = test::call_service(
+    &app,
+    test::TestRequest::post()
+      .uri("/api/v3/post/like")
+      .cookie(actix_web::cookie::Cookie::new("jwt", JWT))
+      .cookie(actix_web::cookie::Cookie::new("theme", "retro"))
+      .set_json(serde_json::json!({ "post_id": 1, "score": 1 }))
+      .to_request(),
+  )
+  .await;
+  assert_eq!(res.status(), StatusCode::OK);
+
+  let votes = mock.requests_to("post/like");
+  assert_eq!(
+    votes[0].authorization.as_deref(),
+    Some(format!("Bearer {JWT}").as_str())
+  );
+}
+
+#[actix_web::test]
+async fn proxy_forwards_peer_address_unless_behind_trusted_proxy() {
+  let mock = MockLemmy::start();
+  let app = app(&mock).await;
+
+  // a browser naming another address to get around lemmy's rate limits
+  let spoofed = || {
+    test::TestRequest::get()
+      .uri("/api/v3/site")
+      .peer_addr("192.0.2.1:40000".parse().unwrap())
+      .insert_header((header::X_FORWARDED_FOR, "203.0.113.7"))
+      .to_request()
+  };
+
+  test::call_service(&app, spoofed()).await;
+  let sites = mock.requests_to("site");
+  assert_eq!(sites[0].forwarded_for.as_deref(), Some("192.0.2.1"));
+
+  // a reverse proxy in front is taken at its word
+  let mut config = mock.config();
+  config.proxy.trusted_proxies = vec!["192.0.2.1".parse().unwrap()];
+  let app = app_with(config).await;
+
+  test::call_service(&app, spoofed()).await;
+  let sites = mock.requests_to("site");
+  assert_eq!(sites[1].forwarded_for.as_deref(), Some("203.0.113.7"));
+}